@@ -1,7 +1,7 @@
 use crate::prelude::*;
 
 /// Abstracts over the current time.
-pub trait Clock {
+pub trait Clock: Send {
     /// Get the current time.
     fn now(&self) -> Time;
 }
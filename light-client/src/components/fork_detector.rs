@@ -2,24 +2,69 @@ use serde::{Deserialize, Serialize};
 
 use crate::prelude::*;
 
+/// Result of fork detection
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ForkDetection {
-    // NOTE: We box the fields to reduce the overall size of the enum.
-    //       See https://rust-lang.github.io/rust-clippy/master/index.html#large_enum_variant
-    Detected(Box<LightBlock>, Box<LightBlock>),
+    /// One or more forks have been detected
+    Detected(Vec<Fork>),
+    /// No fork has been detected
     NotDetected,
 }
 
-pub trait ForkDetector {
-    fn detect(&self, light_blocks: Vec<LightBlock>) -> ForkDetection;
+/// Outcome of cross-checking a light block verified from the primary against
+/// the witnesses.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ForkDetectionReport {
+    /// Result of fork detection against the light blocks which could be
+    /// fetched from the witnesses
+    pub result: ForkDetection,
+    /// Witnesses from which no light block could be fetched, along with
+    /// the corresponding error
+    pub witness_errors: Vec<(PeerId, IoError)>,
+}
+
+/// A fork between the primary and a witness, ie. two conflicting
+/// light blocks for the same height.
+///
+/// The peers which supplied the light blocks are available
+/// via their `provider` field.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Fork {
+    /// Light block verified by the light client, as supplied by the primary
+    pub primary: LightBlock,
+    /// Conflicting light block, as supplied by a witness
+    pub witness: LightBlock,
+}
+
+impl Fork {
+    /// The peer which supplied the verified light block.
+    pub fn primary_peer(&self) -> PeerId {
+        self.primary.provider
+    }
+
+    /// The peer which supplied the conflicting light block.
+    pub fn witness_peer(&self) -> PeerId {
+        self.witness.provider
+    }
+}
+
+/// Interface for a fork detector.
+pub trait ForkDetector: Send {
+    /// Compare the given light block verified from the primary against the
+    /// light blocks at the same height fetched from the witnesses.
+    fn detect(&self, verified_block: &LightBlock, witness_blocks: Vec<LightBlock>)
+        -> ForkDetection;
 }
 
+/// Production implementation of the fork detector, which reports a fork
+/// for every witness block whose header hash differs from the one of the
+/// verified block.
 pub struct ProdForkDetector {
-    header_hasher: Box<dyn HeaderHasher>,
+    header_hasher: Box<dyn HeaderHasher + Send>,
 }
 
 impl ProdForkDetector {
-    pub fn new(header_hasher: impl HeaderHasher + 'static) -> Self {
+    pub fn new(header_hasher: impl HeaderHasher + Send + 'static) -> Self {
         Self {
             header_hasher: Box::new(header_hasher),
         }
@@ -33,22 +78,30 @@ impl Default for ProdForkDetector {
 }
 
 impl ForkDetector for ProdForkDetector {
-    fn detect(&self, mut light_blocks: Vec<LightBlock>) -> ForkDetection {
-        if light_blocks.is_empty() {
-            return ForkDetection::NotDetected;
-        }
-
-        let first_block = light_blocks.pop().unwrap(); // Safety: checked above that not empty.
-        let first_hash = self.header_hasher.hash(&first_block.signed_header.header);
+    fn detect(
+        &self,
+        verified_block: &LightBlock,
+        witness_blocks: Vec<LightBlock>,
+    ) -> ForkDetection {
+        let verified_hash = self
+            .header_hasher
+            .hash(&verified_block.signed_header.header);
 
-        for light_block in light_blocks {
-            let hash = self.header_hasher.hash(&light_block.signed_header.header);
+        let forks = witness_blocks
+            .into_iter()
+            .filter(|witness_block| {
+                self.header_hasher.hash(&witness_block.signed_header.header) != verified_hash
+            })
+            .map(|witness_block| Fork {
+                primary: verified_block.clone(),
+                witness: witness_block,
+            })
+            .collect::<Vec<_>>();
 
-            if first_hash != hash {
-                return ForkDetection::Detected(Box::new(first_block), Box::new(light_block));
-            }
+        if forks.is_empty() {
+            ForkDetection::NotDetected
+        } else {
+            ForkDetection::Detected(forks)
         }
-
-        ForkDetection::NotDetected
     }
 }
@@ -1,11 +1,15 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::sync::{Mutex, PoisonError};
 
-use contracts::{contract_trait, post, pre};
+use async_trait::async_trait;
+use contracts::{contract_trait, post};
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use tendermint::{block, rpc};
+use tendermint::rpc::{self, endpoint::commit, endpoint::validators};
 use thiserror::Error;
+use tokio::runtime::{Handle, Runtime};
 
-use tendermint::block::signed_header::SignedHeader as TMSignedHeader;
 use tendermint::validator::Set as TMValidatorSet;
 
 use crate::prelude::*;
@@ -40,8 +44,64 @@ where
     }
 }
 
+/// Asynchronous variant of the `Io` component, which does not block the current thread
+/// and can thus be used from within an existing async runtime.
+#[async_trait]
+pub trait AsyncIo: Send {
+    /// Fetch a light block at the given height from the peer with the given peer ID.
+    ///
+    /// ## Postcondition
+    /// - The provider of the returned light block matches the given peer [LCV-IO-POST-PROVIDER]
+    ///
+    /// The postcondition is checked when fetching light blocks with the
+    /// [`fetch_light_block_async`](fn.fetch_light_block_async.html) function.
+    async fn fetch_light_block_async(
+        &mut self,
+        peer: PeerId,
+        height: Height,
+    ) -> Result<LightBlock, IoError>;
+}
+
+/// Fetch a light block through the given `AsyncIo` component, checking its
+/// postcondition [LCV-IO-POST-PROVIDER].
+pub async fn fetch_light_block_async(
+    io: &mut dyn AsyncIo,
+    peer: PeerId,
+    height: Height,
+) -> Result<LightBlock, IoError> {
+    let result = io.fetch_light_block_async(peer, height).await;
+    check_provider(peer, result)
+}
+
+/// Contracts can't be attached to async trait methods, so the postcondition of
+/// `AsyncIo::fetch_light_block_async` is checked on its result instead.
+#[post(ret.as_ref().map(|lb| lb.provider == peer).unwrap_or(true))]
+fn check_provider(
+    peer: PeerId,
+    result: Result<LightBlock, IoError>,
+) -> Result<LightBlock, IoError> {
+    result
+}
+
+#[async_trait]
+impl<F> AsyncIo for F
+where
+    F: FnMut(PeerId, Height) -> Result<LightBlock, IoError> + Send,
+{
+    async fn fetch_light_block_async(
+        &mut self,
+        peer: PeerId,
+        height: Height,
+    ) -> Result<LightBlock, IoError> {
+        self(peer, height)
+    }
+}
+
 /// Production implementation of the Io component, which fetches
 /// light blocks from full nodes via RPC.
+///
+/// The signed header and the two validator sets making up a light block
+/// are fetched in a single JSONRPC batch.
 pub struct ProdIo {
     rpc_clients: HashMap<PeerId, rpc::Client>,
     peer_map: HashMap<PeerId, tendermint::net::Address>,
@@ -50,15 +110,30 @@ pub struct ProdIo {
 #[contract_trait]
 impl Io for ProdIo {
     fn fetch_light_block(&mut self, peer: PeerId, height: Height) -> Result<LightBlock, IoError> {
-        let signed_header = self.fetch_signed_header(peer, height)?;
-        let height = signed_header.header.height.into();
+        block_on(self.fetch_light_block_async(peer, height))
+    }
+}
+
+#[async_trait]
+impl AsyncIo for ProdIo {
+    async fn fetch_light_block_async(
+        &mut self,
+        peer: PeerId,
+        height: Height,
+    ) -> Result<LightBlock, IoError> {
+        let rpc_client: &rpc::Client = self.rpc_client_for(peer);
 
-        let validator_set = self.fetch_validator_set(peer, height)?;
-        let next_validator_set = self.fetch_validator_set(peer, height + 1)?;
+        let height = if height == LATEST_HEIGHT {
+            // We need to know the height of the latest block before fetching its validator sets
+            let signed_header = rpc_client.latest_commit().await?.signed_header;
+            signed_header.header.height.into()
+        } else {
+            height
+        };
 
-        let light_block = LightBlock::new(signed_header, validator_set, next_validator_set, peer);
+        let mut light_blocks = fetch_light_blocks(rpc_client, &[height], peer).await?;
 
-        Ok(light_block)
+        Ok(light_blocks.remove(0))
     }
 }
 
@@ -73,40 +148,18 @@ impl ProdIo {
         }
     }
 
-    #[pre(self.peer_map.contains_key(&peer))]
-    fn fetch_signed_header(
-        &mut self,
-        peer: PeerId,
-        height: Height,
-    ) -> Result<TMSignedHeader, IoError> {
-        let height: block::Height = height.into();
-        let rpc_client = self.rpc_client_for(peer);
-
-        let res = block_on(async {
-            match height.value() {
-                0 => rpc_client.latest_commit().await,
-                _ => rpc_client.commit(height).await,
-            }
-        });
-
-        match res {
-            Ok(response) => Ok(response.signed_header),
-            Err(err) => Err(IoError::IoError(err)),
-        }
-    }
-
-    #[pre(self.peer_map.contains_key(&peer))]
-    fn fetch_validator_set(
+    /// Fetch the light blocks at the given heights from the peer with the given peer ID,
+    /// in a single JSONRPC batch.
+    ///
+    /// ## Note
+    /// The heights must be actual block heights, ie. not `LATEST_HEIGHT`.
+    pub async fn fetch_light_blocks_async(
         &mut self,
         peer: PeerId,
-        height: Height,
-    ) -> Result<TMValidatorSet, IoError> {
-        let res = block_on(self.rpc_client_for(peer).validators(height));
-
-        match res {
-            Ok(response) => Ok(TMValidatorSet::new(response.validators)),
-            Err(err) => Err(IoError::IoError(err)),
-        }
+        heights: &[Height],
+    ) -> Result<Vec<LightBlock>, IoError> {
+        let rpc_client: &rpc::Client = self.rpc_client_for(peer);
+        fetch_light_blocks(rpc_client, heights, peer).await
     }
 
     // FIXME: Cannot enable precondition because of "autoref lifetime" issue
@@ -119,11 +172,107 @@ impl ProdIo {
     }
 }
 
-fn block_on<F: std::future::Future>(f: F) -> F::Output {
-    tokio::runtime::Builder::new()
+/// Fetch the signed headers and validator sets making up the light blocks at the
+/// given heights, in a single JSONRPC batch.
+///
+/// Validator sets spanning several pages need a second batch for their other pages.
+async fn fetch_light_blocks(
+    rpc_client: &rpc::Client,
+    heights: &[Height],
+    peer: PeerId,
+) -> Result<Vec<LightBlock>, IoError> {
+    let mut batch = rpc::Batch::new();
+
+    let commits = heights
+        .iter()
+        .map(|&height| batch.add(commit::Request::new(height.into())))
+        .collect::<Vec<_>>();
+
+    // Consecutive light blocks share validator sets
+    let mut first_pages = BTreeMap::new();
+    for &height in heights {
+        for &height in &[height, height + 1] {
+            first_pages
+                .entry(height)
+                .or_insert_with(|| batch.add(validators_page(height, 1)));
+        }
+    }
+
+    let responses = rpc_client.batch(batch).await?;
+
+    let mut batch = rpc::Batch::new();
+    let mut validator_sets = BTreeMap::new();
+    let mut other_pages = vec![];
+
+    for (height, first_page) in first_pages {
+        let response = responses.get(&first_page)?;
+        let per_page = u32::from(validators::MAX_PER_PAGE);
+        let pages = (response.total + per_page - 1) / per_page;
+
+        for page in 2..=pages {
+            other_pages.push((height, batch.add(validators_page(height, page))));
+        }
+
+        validator_sets.insert(height, response.validators);
+    }
+
+    if !batch.is_empty() {
+        let other_responses = rpc_client.batch(batch).await?;
+
+        for (height, page) in other_pages {
+            let validators = validator_sets.get_mut(&height).unwrap();
+            validators.extend(other_responses.get(&page)?.validators);
+        }
+    }
+
+    let validator_set = |height: Height| TMValidatorSet::new(validator_sets[&height].clone());
+
+    heights
+        .iter()
+        .zip(commits)
+        .map(|(&height, commit)| {
+            Ok(LightBlock::new(
+                responses.get(&commit)?.signed_header,
+                validator_set(height),
+                validator_set(height + 1),
+                peer,
+            ))
+        })
+        .collect()
+}
+
+fn validators_page(height: Height, page: u32) -> validators::Request {
+    validators::Request::paginated(height.into(), page, validators::MAX_PER_PAGE)
+}
+
+/// Single-threaded runtime on which the blocking variants of the async methods
+/// are run, so that the connections pooled by `ProdIo` across calls stay bound
+/// to the runtime they were opened on.
+static RUNTIME: Lazy<Mutex<Runtime>> = Lazy::new(|| {
+    let runtime = tokio::runtime::Builder::new()
         .basic_scheduler()
         .enable_all()
         .build()
-        .unwrap()
-        .block_on(f)
+        .unwrap();
+
+    Mutex::new(runtime)
+});
+
+/// Run the given future to completion on the runtime shared by all the blocking
+/// calls, blocking the current thread.
+///
+/// ## Note
+/// Blocking calls made concurrently from several threads are run one after the other.
+///
+/// This will panic if called from within an async runtime,
+/// in which case the future should be awaited instead.
+pub fn block_on<F: Future>(f: F) -> F::Output {
+    assert!(
+        Handle::try_current().is_err(),
+        "cannot block on a future from within an async runtime, await it instead"
+    );
+
+    // A panicking future doesn't leave the runtime in an inconsistent state
+    let mut runtime = RUNTIME.lock().unwrap_or_else(PoisonError::into_inner);
+    runtime.block_on(f)
 }
@@ -6,7 +6,7 @@ use contracts::*;
 /// The scheduler is given access to the light store, in order to optionally
 /// improve performance by picking a next block that has already been fetched.
 #[contract_trait]
-pub trait Scheduler {
+pub trait Scheduler: Send {
     /// Decides what block to verify next.
     ///
     /// ## Precondition
@@ -27,7 +27,7 @@ pub trait Scheduler {
 #[contract_trait]
 impl<F> Scheduler for F
 where
-    F: Fn(&dyn LightStore, Height, Height) -> Height,
+    F: Fn(&dyn LightStore, Height, Height) -> Height + Send,
 {
     fn schedule(
         &self,
@@ -33,7 +33,7 @@ impl From<Result<(), VerificationError>> for Verdict {
 /// ## Implements
 /// - [TMBC-VAL-CONTAINS-CORR.1]
 /// - [TMBC-VAL-COMMIT.1]
-pub trait Verifier {
+pub trait Verifier: Send {
     /// Perform the verification.
     fn verify(&self, untrusted: &LightBlock, trusted: &LightBlock, options: &Options) -> Verdict;
 }
@@ -48,18 +48,18 @@ pub trait Verifier {
 ///
 /// For regular use, one can construct a standard implementation with `ProdVerifier::default()`.
 pub struct ProdVerifier {
-    predicates: Box<dyn VerificationPredicates>,
-    voting_power_calculator: Box<dyn VotingPowerCalculator>,
-    commit_validator: Box<dyn CommitValidator>,
-    header_hasher: Box<dyn HeaderHasher>,
+    predicates: Box<dyn VerificationPredicates + Send>,
+    voting_power_calculator: Box<dyn VotingPowerCalculator + Send>,
+    commit_validator: Box<dyn CommitValidator + Send>,
+    header_hasher: Box<dyn HeaderHasher + Send>,
 }
 
 impl ProdVerifier {
     pub fn new(
-        predicates: impl VerificationPredicates + 'static,
-        voting_power_calculator: impl VotingPowerCalculator + 'static,
-        commit_validator: impl CommitValidator + 'static,
-        header_hasher: impl HeaderHasher + 'static,
+        predicates: impl VerificationPredicates + Send + 'static,
+        voting_power_calculator: impl VotingPowerCalculator + Send + 'static,
+        commit_validator: impl CommitValidator + Send + 'static,
+        header_hasher: impl HeaderHasher + Send + 'static,
     ) -> Self {
         Self {
             predicates: Box::new(predicates),
@@ -85,9 +85,9 @@ impl Verifier for ProdVerifier {
     fn verify(&self, untrusted: &LightBlock, trusted: &TrustedState, options: &Options) -> Verdict {
         preds::verify(
             &*self.predicates,
-            &self.voting_power_calculator,
-            &self.commit_validator,
-            &self.header_hasher,
+            &*self.voting_power_calculator,
+            &*self.commit_validator,
+            &*self.header_hasher,
             &trusted,
             &untrusted,
             options,
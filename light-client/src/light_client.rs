@@ -52,7 +52,7 @@ pub struct LightClient {
     scheduler: Box<dyn Scheduler>,
     verifier: Box<dyn Verifier>,
     fork_detector: Box<dyn ForkDetector>,
-    io: Box<dyn Io>,
+    io: Box<dyn AsyncIo>,
 }
 
 impl LightClient {
@@ -64,7 +64,7 @@ impl LightClient {
         scheduler: impl Scheduler + 'static,
         verifier: impl Verifier + 'static,
         fork_detector: impl ForkDetector + 'static,
-        io: impl Io + 'static,
+        io: impl AsyncIo + 'static,
     ) -> Self {
         Self {
             state,
@@ -80,14 +80,23 @@ impl LightClient {
     /// Attempt to update the light client to the latest block of the primary node.
     ///
     /// Note: This functin delegates the actual work to `verify_to_target`.
+    ///
+    /// This blocks the current thread, and hence cannot be called from within
+    /// an async runtime. Use `verify_to_highest_async` instead in that case.
     pub fn verify_to_highest(&mut self) -> Result<LightBlock, Error> {
+        block_on(self.verify_to_highest_async())
+    }
+
+    /// Async variant of `verify_to_highest`.
+    pub async fn verify_to_highest_async(&mut self) -> Result<LightBlock, Error> {
         let peer = self.state.peers.primary;
-        let target_block = match self.io.fetch_light_block(peer, LATEST_HEIGHT) {
-            Ok(last_block) => last_block,
-            Err(io_error) => bail!(ErrorKind::Io(io_error)),
-        };
+        let target_block =
+            match fetch_light_block_async(self.io.as_mut(), peer, LATEST_HEIGHT).await {
+                Ok(last_block) => last_block,
+                Err(io_error) => bail!(ErrorKind::Io(io_error)),
+            };
 
-        self.verify_to_target(target_block.height())
+        self.verify_to_target_async(target_block.height()).await
     }
 
     /// Attemps to update the light client to a block of the primary node at the given height.
@@ -119,6 +128,10 @@ impl LightClient {
     /// - If the core verification loop invariant is violated [LCV-INV-TP.1]
     /// - If verification of a light block fails
     /// - If it cannot fetch a block from the blockchain
+    ///
+    /// ## Note
+    /// This blocks the current thread, and hence cannot be called from within
+    /// an async runtime. Use `verify_to_target_async` instead in that case.
     // #[pre(
     //     light_store_contains_block_within_trusting_period(
     //         self.state.light_store.as_ref(),
@@ -133,6 +146,17 @@ impl LightClient {
         )
     )]
     pub fn verify_to_target(&mut self, target_height: Height) -> Result<LightBlock, Error> {
+        block_on(self.verify_to_target_async(target_height))
+    }
+
+    /// Async variant of `verify_to_target`, which does not block the current thread
+    /// while fetching light blocks and can thus be awaited from within an async runtime.
+    ///
+    /// See `verify_to_target` for the full documentation.
+    pub async fn verify_to_target_async(
+        &mut self,
+        target_height: Height,
+    ) -> Result<LightBlock, Error> {
         // Override the `now` fields in the given verification options with the current time,
         // as per the given `clock`.
         let options = self.options.with_now(self.clock.now());
@@ -165,8 +189,9 @@ impl LightClient {
             }
 
             // Fetch the block at the current height from the primary node
-            let current_block =
-                self.get_or_fetch_block(self.state.peers.primary, current_height)?;
+            let current_block = self
+                .get_or_fetch_block(self.state.peers.primary, current_height)
+                .await?;
 
             // Validate and verify the current block
             let verdict = self
@@ -208,22 +233,41 @@ impl LightClient {
         }
     }
 
-    /// TODO
-    pub fn detect_forks(&self) -> Result<(), Error> {
-        let light_blocks = self
-            .state
-            .light_store
-            .all(VerifiedStatus::Verified)
-            .collect();
+    /// Cross-check the given light block, verified from the primary, against the
+    /// light blocks at the same height provided by each of the witnesses.
+    ///
+    /// The report's result is `ForkDetection::Detected` with one `Fork` per witness
+    /// which disagrees with the primary, in which case the primary should not be
+    /// trusted anymore.
+    ///
+    /// A witness which cannot be reached does not prevent cross-checking against
+    /// the other ones: its error is recorded in the report's `witness_errors`.
+    ///
+    /// ## Note
+    /// This blocks the current thread, and hence cannot be called from within
+    /// an async runtime. Use `detect_forks_async` instead in that case.
+    pub fn detect_forks(&mut self, light_block: &LightBlock) -> ForkDetectionReport {
+        block_on(self.detect_forks_async(light_block))
+    }
 
-        let result = self.fork_detector.detect(light_blocks);
+    /// Async variant of `detect_forks`.
+    pub async fn detect_forks_async(&mut self, light_block: &LightBlock) -> ForkDetectionReport {
+        let height = light_block.height();
 
-        match result {
-            ForkDetection::NotDetected => (),    // TODO
-            ForkDetection::Detected(_, _) => (), // TODO
+        let mut witness_blocks = Vec::with_capacity(self.state.peers.witnesses.len());
+        let mut witness_errors = Vec::new();
+
+        for witness in &self.state.peers.witnesses {
+            match fetch_light_block_async(self.io.as_mut(), *witness, height).await {
+                Ok(witness_block) => witness_blocks.push(witness_block),
+                Err(e) => witness_errors.push((*witness, e)),
+            }
         }
 
-        Ok(())
+        ForkDetectionReport {
+            result: self.fork_detector.detect(light_block, witness_blocks),
+            witness_errors,
+        }
     }
 
     /// Get the verification trace for the block at target_height.
@@ -236,9 +280,7 @@ impl LightClient {
     ///
     /// ## Postcondition
     /// - The provider of block that is returned matches the given peer.
-    // TODO: Uncomment when provider field is available
-    // #[post(ret.map(|lb| lb.provider == peer).unwrap_or(false))]
-    fn get_or_fetch_block(
+    async fn get_or_fetch_block(
         &mut self,
         peer: PeerId,
         current_height: Height,
@@ -247,27 +289,38 @@ impl LightClient {
             .state
             .light_store
             .get(current_height, VerifiedStatus::Verified)
-            // .filter(|lb| lb.provider == peer)
+            .filter(|lb| lb.provider == peer)
             .or_else(|| {
                 self.state
                     .light_store
                     .get(current_height, VerifiedStatus::Unverified)
-                // .filter(|lb| lb.provider == peer)
+                    .filter(|lb| lb.provider == peer)
             });
 
-        if let Some(current_block) = current_block {
-            return Ok(current_block);
-        }
+        let result: Result<LightBlock, Error> = match current_block {
+            Some(current_block) => Ok(current_block),
+            None => fetch_light_block_async(self.io.as_mut(), peer, current_height)
+                .await
+                .map(|current_block| {
+                    self.state
+                        .light_store
+                        .insert(current_block.clone(), VerifiedStatus::Unverified);
 
-        self.io
-            .fetch_light_block(peer, current_height)
-            .map(|current_block| {
-                self.state
-                    .light_store
-                    .insert(current_block.clone(), VerifiedStatus::Unverified);
+                    current_block
+                })
+                .map_err(|e| ErrorKind::Io(e).into()),
+        };
 
-                current_block
-            })
-            .map_err(|e| ErrorKind::Io(e).into())
+        check_block_provider(peer, result)
     }
 }
+
+/// Contracts can't be attached to async functions, so the postcondition of
+/// `LightClient::get_or_fetch_block` is checked on its result instead.
+#[post(ret.as_ref().map(|lb| lb.provider == peer).unwrap_or(true))]
+fn check_block_provider(
+    peer: PeerId,
+    result: Result<LightBlock, Error>,
+) -> Result<LightBlock, Error> {
+    result
+}
@@ -43,7 +43,7 @@ impl VerifiedStatus {
 ///
 /// ## Implements
 /// - [LCV-DIST-STORE.1]
-pub trait LightStore: std::fmt::Debug {
+pub trait LightStore: std::fmt::Debug + Send {
     /// Get the light block at the given height with the given status, or return `None` otherwise.
     fn get(&self, height: Height, status: VerifiedStatus) -> Option<LightBlock>;
     /// Update the `status` of the given `light_block`.
@@ -7,6 +7,7 @@ use std::convert::TryInto;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use async_trait::async_trait;
 use contracts::contract_trait;
 use tendermint::rpc;
 
@@ -118,6 +119,16 @@ impl MockIo {
 #[contract_trait]
 impl Io for MockIo {
     fn fetch_light_block(&mut self, _peer: PeerId, height: Height) -> Result<LightBlock, IoError> {
+        let height = if height == LATEST_HEIGHT {
+            self.light_blocks
+                .keys()
+                .max()
+                .copied()
+                .unwrap_or(LATEST_HEIGHT)
+        } else {
+            height
+        };
+
         self.light_blocks
             .get(&height)
             .cloned()
@@ -125,6 +136,17 @@ impl Io for MockIo {
     }
 }
 
+#[async_trait]
+impl AsyncIo for MockIo {
+    async fn fetch_light_block_async(
+        &mut self,
+        peer: PeerId,
+        height: Height,
+    ) -> Result<LightBlock, IoError> {
+        self.fetch_light_block(peer, height)
+    }
+}
+
 struct MockClock {
     now: Time,
 }
@@ -144,11 +166,10 @@ fn verify_bisection(
         .map(|_| light_client.get_trace(untrusted_height))
 }
 
-fn run_bisection_test(tc: TestBisection<LightBlock>) {
-    println!("  - {}", tc.description);
-
+/// Build a light client for the given bisection test case, trusting the light
+/// block at the trusted height of the primary
+fn bisection_light_client(tc: &TestBisection<LightBlock>, io: &mut MockIo) -> LightClient {
     let primary = default_peer_id();
-    let untrusted_height = tc.height_to_verify.try_into().unwrap();
     let trust_threshold = tc.trust_options.trust_level;
     let trusting_period = tc.trust_options.period;
     let now = tc.now;
@@ -166,17 +187,9 @@ fn run_bisection_test(tc: TestBisection<LightBlock>) {
         now,
     };
 
-    let expects_err = match &tc.expected_output {
-        Some(eo) => eo.eq("error"),
-        None => false,
-    };
-
-    let provider = tc.primary;
-    let mut io = MockIo::new(provider.chain_id, provider.lite_blocks);
-
     let trusted_height = tc.trust_options.height.try_into().unwrap();
     let trusted_state = io
-        .fetch_light_block(primary.clone(), trusted_height)
+        .fetch_light_block(primary, trusted_height)
         .expect("could not 'request' light block");
 
     let mut light_store = MemoryStore::new();
@@ -184,7 +197,7 @@ fn run_bisection_test(tc: TestBisection<LightBlock>) {
 
     let state = State {
         peers: Peers {
-            primary: primary.clone(),
+            primary,
             witnesses: vec![],
         },
         light_store: Box::new(light_store),
@@ -193,7 +206,7 @@ fn run_bisection_test(tc: TestBisection<LightBlock>) {
 
     let verifier = ProdVerifier::default();
 
-    let mut light_client = LightClient::new(
+    LightClient::new(
         state,
         options,
         clock,
@@ -201,12 +214,28 @@ fn run_bisection_test(tc: TestBisection<LightBlock>) {
         verifier,
         fork_detector,
         io.clone(),
-    );
+    )
+}
+
+fn run_bisection_test(tc: TestBisection<LightBlock>) {
+    println!("  - {}", tc.description);
+
+    let primary = default_peer_id();
+    let untrusted_height = tc.height_to_verify.try_into().unwrap();
+
+    let expects_err = match &tc.expected_output {
+        Some(eo) => eo.eq("error"),
+        None => false,
+    };
+
+    let provider = tc.primary.clone();
+    let mut io = MockIo::new(provider.chain_id, provider.lite_blocks);
+    let mut light_client = bisection_light_client(&tc, &mut io);
 
     match verify_bisection(untrusted_height, &mut light_client) {
         Ok(new_states) => {
             let untrusted_light_block = io
-                .fetch_light_block(primary.clone(), untrusted_height)
+                .fetch_light_block(primary, untrusted_height)
                 .expect("header at untrusted height not found");
 
             // TODO: number of bisections started diverting in JSON tests and Rust impl
@@ -226,6 +255,86 @@ fn run_bisection_test(tc: TestBisection<LightBlock>) {
     }
 }
 
+fn run_fork_detection_test(tc: TestBisection<LightBlock>) {
+    println!("  - {}", tc.description);
+
+    let primary = default_peer_id();
+    let honest_witness: PeerId = "DEADBEEFDEADBEEFDEADBEEFDEADBEEFDEADBEEF".parse().unwrap();
+    let faulty_witness: PeerId = "CEFEEDCEFEEDCEFEEDCEFEEDCEFEEDCEFEEDCEFE".parse().unwrap();
+    let unreachable_witness: PeerId = "BADC0FFEEBADC0FFEEBADC0FFEEBADC0FFEEBADC".parse().unwrap();
+
+    let clock = MockClock { now: tc.now };
+    let options = Options {
+        trust_threshold: tc.trust_options.trust_level,
+        trusting_period: tc.trust_options.period.into(),
+        clock_drift: Duration::from_secs(1),
+        now: tc.now,
+    };
+
+    let provider = tc.primary;
+    let mut primary_io = MockIo::new(provider.chain_id, provider.lite_blocks);
+
+    let trusted_height = tc.trust_options.height.try_into().unwrap();
+    let trusted_state = primary_io
+        .fetch_light_block(primary, trusted_height)
+        .expect("could not 'request' light block");
+
+    let mut light_store = MemoryStore::new();
+    light_store.insert(trusted_state.clone(), VerifiedStatus::Verified);
+
+    let state = State {
+        peers: Peers {
+            primary,
+            witnesses: vec![unreachable_witness, honest_witness, faulty_witness],
+        },
+        light_store: Box::new(light_store),
+        verification_trace: HashMap::new(),
+    };
+
+    // The faulty witness serves a header with a different app hash than the primary,
+    // and the unreachable one, which is queried first, doesn't serve anything.
+    let io = move |peer: PeerId, height: Height| -> Result<LightBlock, IoError> {
+        if peer == unreachable_witness {
+            return Err(rpc::Error::http_error("connection refused").into());
+        }
+
+        let mut light_block = primary_io.fetch_light_block(primary, height)?;
+        light_block.provider = peer;
+
+        if peer == faulty_witness {
+            light_block.signed_header.header.app_hash = b"forged_app_hash".to_vec();
+        }
+
+        Ok(light_block)
+    };
+
+    let mut light_client = LightClient::new(
+        state,
+        options,
+        clock,
+        scheduler::basic_bisecting_schedule,
+        ProdVerifier::default(),
+        ProdForkDetector::default(),
+        io,
+    );
+
+    let report = light_client.detect_forks(&trusted_state);
+
+    assert_eq!(report.witness_errors.len(), 1);
+    assert_eq!(report.witness_errors[0].0, unreachable_witness);
+
+    match report.result {
+        ForkDetection::Detected(forks) => {
+            assert_eq!(forks.len(), 1);
+            assert_eq!(forks[0].primary, trusted_state);
+            assert_eq!(forks[0].primary_peer(), primary);
+            assert_eq!(forks[0].witness_peer(), faulty_witness);
+            assert_eq!(forks[0].witness.height(), trusted_state.height());
+        }
+        ForkDetection::NotDetected => panic!("expected a fork to be detected"),
+    }
+}
+
 fn run_single_step_tests(dir: &str) {
     // TODO: this test need further investigation:
     let skipped = ["commit/one_third_vals_don't_sign.json"];
@@ -314,3 +423,63 @@ fn single_step_skipping() {
         run_single_step_tests(dir);
     }
 }
+
+/// The async variants of the verification methods can be awaited from within
+/// a runtime, where the blocking ones would panic
+#[tokio::test]
+async fn bisection_async() {
+    let file_path = PathBuf::from(TEST_FILES_PATH).join("bisection/single_peer/happy_path.json");
+    let tc = read_bisection_test_case(&format!("{}", file_path.display()));
+
+    let provider = tc.primary.clone();
+    let mut io = MockIo::new(provider.chain_id, provider.lite_blocks);
+    let untrusted_height = tc.height_to_verify.try_into().unwrap();
+    let untrusted_light_block = io
+        .fetch_light_block(default_peer_id(), untrusted_height)
+        .unwrap();
+
+    let mut light_client = bisection_light_client(&tc, &mut io);
+    let verified = light_client
+        .verify_to_target_async(untrusted_height)
+        .await
+        .unwrap();
+    assert_eq!(verified, untrusted_light_block);
+
+    let latest_light_block = io
+        .fetch_light_block(default_peer_id(), LATEST_HEIGHT)
+        .unwrap();
+
+    let mut light_client = bisection_light_client(&tc, &mut io);
+    let verified = light_client.verify_to_highest_async().await.unwrap();
+    assert_eq!(verified, latest_light_block);
+}
+
+/// The verification futures are `Send`, and can thus be spawned onto a runtime
+#[tokio::test]
+async fn spawn_verification() {
+    let file_path = PathBuf::from(TEST_FILES_PATH).join("bisection/single_peer/happy_path.json");
+    let tc = read_bisection_test_case(&format!("{}", file_path.display()));
+
+    let provider = tc.primary.clone();
+    let mut io = MockIo::new(provider.chain_id, provider.lite_blocks);
+    let untrusted_height = tc.height_to_verify.try_into().unwrap();
+    let untrusted_light_block = io
+        .fetch_light_block(default_peer_id(), untrusted_height)
+        .unwrap();
+
+    let mut light_client = bisection_light_client(&tc, &mut io);
+    let verified =
+        tokio::spawn(async move { light_client.verify_to_target_async(untrusted_height).await })
+            .await
+            .unwrap()
+            .unwrap();
+
+    assert_eq!(verified, untrusted_light_block);
+}
+
+#[test]
+fn fork_detection() {
+    let file_path = PathBuf::from(TEST_FILES_PATH).join("bisection/single_peer/happy_path.json");
+    let case = read_bisection_test_case(&format!("{}", file_path.display()));
+    run_fork_detection_test(case);
+}
@@ -5,20 +5,30 @@
 use crate::prelude::*;
 
 use tendermint::hash;
-use tendermint::lite;
 use tendermint::lite::ValidatorSet as _;
-use tendermint::lite::{Header, Height, Requester, TrustThresholdFraction};
-use tendermint::rpc;
+use tendermint::net;
 use tendermint::Hash;
 
+use tendermint_light_client::components::clock::SystemClock;
+use tendermint_light_client::components::fork_detector::{ForkDetection, ProdForkDetector};
+use tendermint_light_client::components::io::{AsyncIo, ProdIo};
+use tendermint_light_client::components::scheduler;
+use tendermint_light_client::components::verifier::ProdVerifier;
+use tendermint_light_client::errors::{Error, ErrorKind};
+use tendermint_light_client::light_client::{self, LightClient};
+use tendermint_light_client::predicates::errors::VerificationError;
+use tendermint_light_client::state::{Peers, State};
+use tendermint_light_client::store::{sled::SledStore, LightStore, VerifiedStatus};
+use tendermint_light_client::types::{Height, PeerId, Time};
+
 use crate::application::APPLICATION;
 use crate::config::LightNodeConfig;
-use crate::requester::RPCRequester;
-use crate::store::{MemStore, State};
-use abscissa_core::{config, Command, FrameworkError, Options, Runnable};
+use crate::rpc::StoreHandler;
+use abscissa_core::{config, Command, FrameworkError, FrameworkErrorKind, Options, Runnable};
+use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::process;
-use std::time::{Duration, SystemTime};
-use tendermint::lite::error::Error;
+use std::time::Duration;
 
 /// `start` subcommand
 ///
@@ -29,7 +39,7 @@ use tendermint::lite::error::Error;
 /// <https://docs.rs/gumdrop/>
 #[derive(Command, Debug, Options)]
 pub struct StartCmd {
-    /// RPC address to request headers and validators from.
+    /// RPC address of the primary to request headers and validators from.
     #[options(free)]
     rpc_addr: String,
 }
@@ -40,57 +50,123 @@ impl Runnable for StartCmd {
         if let Err(err) = abscissa_tokio::run(&APPLICATION, async {
             let config = app_config();
 
-            let client = rpc::Client::new(config.rpc_address.parse().unwrap());
-            let req = RPCRequester::new(client);
-            let mut store = MemStore::new();
-
-            let vals_hash = Hash::from_hex_upper(
-                hash::Algorithm::Sha256,
-                &config.subjective_init.validators_hash,
-            )
-            .unwrap();
+            let primary = config.primary.peer_id;
 
-            println!("Requesting from {}.", config.rpc_address);
+            let mut peer_map = HashMap::new();
+            peer_map.insert(primary, config.primary.address.clone());
+            for witness in &config.witnesses {
+                peer_map.insert(witness.peer_id, witness.address.clone());
+            }
 
-            subjective_init(config.subjective_init.height, vals_hash, &mut store, &req)
-                .await
-                .unwrap();
+            let mut io = ProdIo::new(peer_map);
 
-            loop {
-                let latest_sh = (&req).signed_header(0).await.unwrap();
-                let latest_peer_height = latest_sh.header().height();
+            let db = sled::open(&config.db_path).unwrap_or_else(|e| {
+                eprintln!("could not open database: {}", e);
+                process::exit(1);
+            });
 
-                let latest_trusted = store.get(0).unwrap();
-                let latest_trusted_height = latest_trusted.last_header().header().height();
+            let mut light_store = SledStore::new(db.clone());
 
-                // only bisect to higher heights
-                if latest_peer_height <= latest_trusted_height {
-                    std::thread::sleep(Duration::new(1, 0));
-                    continue;
-                }
+            println!("Requesting from {}.", config.primary.address);
 
+            if let Some(latest_trusted) = light_store.latest(VerifiedStatus::Verified) {
                 println!(
-                    "attempting bisection from height {:?} to height {:?}",
-                    latest_trusted_height, latest_peer_height,
+                    "Resuming from latest trusted height {}.",
+                    latest_trusted.height()
                 );
-
-                let now = SystemTime::now();
-                lite::verify_bisection(
-                    latest_trusted.to_owned(),
-                    latest_peer_height,
-                    TrustThresholdFraction::default(), // TODO
-                    config.trusting_period,
-                    now,
-                    &req,
+            } else {
+                let vals_hash = Hash::from_hex_upper(
+                    hash::Algorithm::Sha256,
+                    &config.subjective_init.validators_hash,
+                )
+                .unwrap_or_else(|e| {
+                    eprintln!("invalid subjective_init.validators_hash: {}", e);
+                    process::exit(1);
+                });
+
+                if let Err(e) = subjective_init(
+                    config.subjective_init.height,
+                    vals_hash,
+                    primary,
+                    &mut light_store,
+                    &mut io,
                 )
                 .await
-                .unwrap();
+                {
+                    eprintln!("could not initialize trusted state: {}", e);
+                    process::exit(1);
+                }
+            }
+
+            let listen_addr: SocketAddr = config.rpc_listen_address.parse().unwrap_or_else(|e| {
+                eprintln!("invalid rpc_listen_address: {}", e);
+                process::exit(1);
+            });
+            let handler = StoreHandler::new(SledStore::new(db));
 
-                println!("Succeeded bisecting!");
+            println!("Serving RPC on {}.", listen_addr);
 
-                // notifications ?
+            tokio::spawn(async move {
+                if let Err(e) = crate::rpc::serve(listen_addr, handler).await {
+                    eprintln!("RPC server error: {}", e);
+                }
+            });
+
+            let state = State {
+                peers: Peers {
+                    primary,
+                    witnesses: config.witnesses.iter().map(|w| w.peer_id).collect(),
+                },
+                light_store: Box::new(light_store),
+                verification_trace: HashMap::new(),
+            };
+
+            let options = light_client::Options {
+                trust_threshold: config.trust_threshold,
+                trusting_period: config.trusting_period,
+                clock_drift: config.clock_drift,
+                now: Time::now(),
+            };
+
+            let mut light_client = LightClient::new(
+                state,
+                options,
+                SystemClock,
+                scheduler::basic_bisecting_schedule,
+                ProdVerifier::default(),
+                ProdForkDetector::default(),
+                io,
+            );
+
+            loop {
+                match light_client.verify_to_highest_async().await {
+                    Ok(light_block) => {
+                        println!("Synced to block {}.", light_block.height());
+
+                        let report = light_client.detect_forks_async(&light_block).await;
+
+                        for (witness, e) in report.witness_errors {
+                            eprintln!("Could not cross-check with witness {}: {}", witness, e);
+                        }
+
+                        if let ForkDetection::Detected(forks) = report.result {
+                            // TODO: publish the evidence, see ADR-005
+                            for fork in forks {
+                                eprintln!(
+                                    "Fork detected at height {}: primary {} conflicts with witness {}",
+                                    fork.primary.height(),
+                                    fork.primary_peer(),
+                                    fork.witness_peer(),
+                                );
+                            }
+
+                            process::exit(1);
+                        }
+                    }
+                    Err(e) => eprintln!("Sync failed: {}", e),
+                }
 
-                // sleep for a few secs ?
+                tokio::time::delay_for(Duration::from_millis(800)).await;
             }
         }) {
             eprintln!("Error while running application: {}", err);
@@ -108,52 +184,44 @@ impl config::Override<LightNodeConfig> for StartCmd {
         mut config: LightNodeConfig,
     ) -> Result<LightNodeConfig, FrameworkError> {
         if !self.rpc_addr.is_empty() {
-            config.rpc_address = self.rpc_addr.to_owned();
+            config.primary.address = self
+                .rpc_addr
+                .parse::<net::Address>()
+                .map_err(|e| FrameworkErrorKind::ConfigError.context(e))?;
         }
 
         Ok(config)
     }
 }
 
-/*
- * The following is initialization logic that should have a
- * function in the lite crate like:
- * `subjective_init(height, vals_hash, store, requester) -> Result<(), Error`
- * it would fetch the initial header/vals from the requester and populate a
- * trusted state and store it in the store ...
- * TODO: this should take traits ... but how to deal with the State ?
- * TODO: better name ?
- */
+/// Fetch the light block at the given height from the primary, check that its
+/// validator set matches the given subjective validators hash, and add it to the
+/// light store as the initial trusted state.
 async fn subjective_init(
     height: Height,
     vals_hash: Hash,
-    store: &mut MemStore,
-    req: &RPCRequester,
+    primary: PeerId,
+    light_store: &mut dyn LightStore,
+    io: &mut ProdIo,
 ) -> Result<(), Error> {
-    if store.get(height).is_ok() {
-        // we already have this !
-        return Ok(());
-    }
+    let light_block = io
+        .fetch_light_block_async(primary, height)
+        .await
+        .map_err(ErrorKind::Io)?;
 
     // check that the val hash matches
-    let vals = req.validator_set(height).await?;
-
-    if vals.hash() != vals_hash {
-        // TODO
-        panic!("vals hash dont match")
+    let validators_hash = light_block.validators.hash();
+    if validators_hash != vals_hash {
+        return Err(
+            ErrorKind::InvalidLightBlock(VerificationError::InvalidValidatorSet {
+                header_validators_hash: vals_hash,
+                validators_hash,
+            })
+            .into(),
+        );
     }
 
-    let signed_header = req.signed_header(height).await?;
-
-    // TODO: validate signed_header.commit() with the vals ...
-
-    let next_vals = req.validator_set(height + 1).await?;
-
-    // TODO: check next_vals ...
-
-    let trusted_state = &State::new(signed_header, next_vals);
-
-    store.add(trusted_state.to_owned())?;
+    light_store.insert(light_block, VerifiedStatus::Verified);
 
     Ok(())
 }
@@ -5,16 +5,30 @@
 //! for specifying it.
 
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use std::time::Duration;
+use tendermint::net;
+use tendermint_light_client::types::{PeerId, TrustThreshold};
 
 /// LightNode Configuration
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct LightNodeConfig {
-    /// RPC address to request headers and validators from.
-    pub rpc_address: String,
+    /// The primary full node, from which light blocks are fetched and verified.
+    pub primary: PeerConfig,
+    /// Full nodes used as witnesses to detect forks.
+    pub witnesses: Vec<PeerConfig>,
+    /// Address on which the light node serves its own RPC endpoint.
+    pub rpc_listen_address: String,
     /// The duration until we consider a trusted state as expired.
     pub trusting_period: Duration,
+    /// The fraction of the total voting power of a trusted validator set
+    /// which must have signed a commit for it to be trusted.
+    pub trust_threshold: TrustThreshold,
+    /// Correction parameter dealing with only approximately synchronized clocks.
+    pub clock_drift: Duration,
+    /// Path to the database holding the light store.
+    pub db_path: PathBuf,
     /// Subjective initialization.
     pub subjective_init: SubjectiveInit,
 }
@@ -26,16 +40,45 @@ pub struct LightNodeConfig {
 impl Default for LightNodeConfig {
     fn default() -> Self {
         Self {
-            rpc_address: "localhost:26657".to_owned(),
+            primary: PeerConfig::default(),
+            witnesses: vec![],
+            rpc_listen_address: "127.0.0.1:8888".to_owned(),
             trusting_period: Duration::new(6000, 0),
+            trust_threshold: TrustThreshold::default(),
+            clock_drift: Duration::new(1, 0),
+            db_path: PathBuf::from("./lightstore"),
             subjective_init: SubjectiveInit::default(),
         }
     }
 }
 
+/// Configuration for a full node the light node connects to.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PeerConfig {
+    /// Peer ID of the full node.
+    pub peer_id: PeerId,
+    /// RPC address to request headers and validators from.
+    pub address: net::Address,
+}
+
+impl Default for PeerConfig {
+    fn default() -> Self {
+        Self {
+            // TODO: a default peer ID here does not make sense unless it belongs
+            // to a node of a public network
+            peer_id: "BADFADAD0BEFEEDC0C0ADEADBEEFC0FFEEFACADE".parse().unwrap(),
+            address: "tcp://127.0.0.1:26657".parse().unwrap(),
+        }
+    }
+}
+
 /// Configuration for subjective initialization.
 ///
 /// Contains the subjective height and validators hash (as a string formatted as hex).
+///
+/// Only used if the light store does not contain any verified light block yet,
+/// otherwise the light node resumes from the latest verified light block.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct SubjectiveInit {
@@ -21,5 +21,4 @@ pub mod commands;
 pub mod config;
 pub mod error;
 pub mod prelude;
-pub mod requester;
-pub mod store;
+pub mod rpc;
@@ -0,0 +1,351 @@
+//! JSONRPC server exposing the data verified by the light node.
+//!
+//! The server speaks the same JSONRPC over HTTP dialect as a Tendermint full node,
+//! and reuses the request and response types of `tendermint::rpc::endpoint`.
+//! It currently supports the following methods:
+//!
+//! - `commit`: get the verified signed header at a given height, or the latest
+//!   trusted one (and thus the latest trusted height) if no height is given.
+//! - `validators`: get a page of the verified validator set at a given height.
+//!
+//! Requests for heights without verified data fail with a server error.
+
+use std::cmp;
+use std::convert::Infallible;
+use std::fmt;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use hyper::header;
+use hyper::service::{make_service_fn, service_fn};
+use serde::{de::DeserializeOwned, Serialize};
+
+use tendermint::rpc::{self, endpoint::commit, endpoint::validators, request, response, Method};
+
+use tendermint_light_client::store::{LightStore, VerifiedStatus};
+use tendermint_light_client::types::Height;
+
+/// Provides the verified data served by the RPC server.
+pub trait Handler: Send + Sync + 'static {
+    /// `/commit`: get the verified signed header at the given height,
+    /// or the latest trusted one if no height is given.
+    fn commit(&self, request: commit::Request) -> Result<commit::Response, rpc::Error>;
+
+    /// `/validators`: get the verified validator set at the given height.
+    fn validators(&self, request: validators::Request) -> Result<validators::Response, rpc::Error>;
+}
+
+/// Serves the verified light blocks held in a light store.
+pub struct StoreHandler<S> {
+    store: S,
+}
+
+impl<S: LightStore> StoreHandler<S> {
+    /// Create a new handler serving the verified light blocks in the given store.
+    pub fn new(store: S) -> Self {
+        Self { store }
+    }
+}
+
+impl<S> Handler for StoreHandler<S>
+where
+    S: LightStore + Send + Sync + 'static,
+{
+    fn commit(&self, request: commit::Request) -> Result<commit::Response, rpc::Error> {
+        let light_block = match request.height() {
+            Some(height) => self.store.get(height.value(), VerifiedStatus::Verified),
+            None => self.store.latest(VerifiedStatus::Verified),
+        };
+
+        let light_block = light_block.ok_or_else(|| {
+            unverified_height(
+                request
+                    .height()
+                    .map_or_else(|| "latest".to_owned(), |h| h.to_string()),
+            )
+        })?;
+
+        Ok(commit::Response {
+            signed_header: light_block.signed_header,
+            canonical: true,
+        })
+    }
+
+    fn validators(&self, request: validators::Request) -> Result<validators::Response, rpc::Error> {
+        let height: Height = request.height().value();
+
+        // The validator set at height H is either the one of the light block at height H,
+        // or the next validator set of the light block at height H-1.
+        let validators = self
+            .store
+            .get(height, VerifiedStatus::Verified)
+            .map(|light_block| light_block.validators)
+            .or_else(|| {
+                height
+                    .checked_sub(1)
+                    .and_then(|h| self.store.get(h, VerifiedStatus::Verified))
+                    .map(|light_block| light_block.next_validators)
+            })
+            .ok_or_else(|| unverified_height(height))?;
+
+        let validators = validators.validators();
+        let total = validators.len();
+        let per_page = validate_per_page(request.per_page());
+        let page = validate_page(request.page(), per_page, total)?;
+        let page = validators
+            .iter()
+            .skip((page - 1) * per_page)
+            .take(per_page)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        Ok(validators::Response {
+            block_height: request.height(),
+            count: page.len() as u32,
+            total: total as u32,
+            validators: page,
+        })
+    }
+}
+
+/// Error returned for heights at which the light node has no verified data
+fn unverified_height(height: impl fmt::Display) -> rpc::Error {
+    rpc::Error::server_error(format!("no verified data at height {}", height))
+}
+
+/// Number of validators per page, defaulting to `DEFAULT_PER_PAGE` and capped
+/// to `MAX_PER_PAGE` as by full nodes
+fn validate_per_page(per_page: u8) -> usize {
+    match per_page {
+        0 => validators::DEFAULT_PER_PAGE.into(),
+        per_page => per_page.min(validators::MAX_PER_PAGE).into(),
+    }
+}
+
+/// Check that the given page of validators exists, page 0 standing for the
+/// first one as with full nodes
+fn validate_page(page: u32, per_page: usize, total: usize) -> Result<usize, rpc::Error> {
+    let pages = cmp::max(1, (total + per_page - 1) / per_page);
+
+    match page as usize {
+        0 => Ok(1),
+        page if page <= pages => Ok(page),
+        page => Err(rpc::Error::invalid_params(&format!(
+            "page should be within [1, {}] range, given {}",
+            pages, page
+        ))),
+    }
+}
+
+/// Run the RPC server on the given address until it fails.
+pub async fn serve<H: Handler>(addr: SocketAddr, handler: H) -> Result<(), hyper::Error> {
+    let handler = Arc::new(handler);
+
+    let make_service = make_service_fn(move |_conn| {
+        let handler = handler.clone();
+
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                handle_http_request(handler.clone(), req)
+            }))
+        }
+    });
+
+    hyper::Server::bind(&addr).serve(make_service).await
+}
+
+async fn handle_http_request<H: Handler>(
+    handler: Arc<H>,
+    request: hyper::Request<hyper::Body>,
+) -> Result<hyper::Response<hyper::Body>, Infallible> {
+    let response_body = match hyper::body::to_bytes(request.into_body()).await {
+        Ok(request_body) => handle(handler.as_ref(), &request_body),
+        Err(e) => serialize_response(rpc::Id::None, Err(rpc::Error::http_error(e.to_string()))),
+    };
+
+    let mut response = hyper::Response::new(hyper::Body::from(response_body));
+    response
+        .headers_mut()
+        .insert(header::CONTENT_TYPE, "application/json".parse().unwrap());
+
+    Ok(response)
+}
+
+/// Handle a single JSONRPC request, returning the JSON-encoded response.
+pub fn handle<H: Handler>(handler: &H, request_body: &[u8]) -> String {
+    let wrapper: request::Wrapper<serde_json::Value> = match serde_json::from_slice(request_body) {
+        Ok(wrapper) => wrapper,
+        Err(e) => return serialize_response(rpc::Id::None, Err(rpc::Error::parse_error(e))),
+    };
+
+    let id = wrapper.id().clone();
+
+    let result = match wrapper.version().ensure_supported() {
+        Ok(()) => dispatch(handler, wrapper.method(), wrapper.into_params()),
+        Err(e) => Err(e),
+    };
+
+    serialize_response(id, result)
+}
+
+fn dispatch<H: Handler>(
+    handler: &H,
+    method: Method,
+    params: serde_json::Value,
+) -> Result<serde_json::Value, rpc::Error> {
+    match method {
+        Method::Commit => to_result(handler.commit(parse_params(params)?)?),
+        Method::Validators => to_result(handler.validators(parse_params(params)?)?),
+        other => Err(rpc::Error::method_not_found(other.as_str())),
+    }
+}
+
+fn parse_params<R: DeserializeOwned>(params: serde_json::Value) -> Result<R, rpc::Error> {
+    serde_json::from_value(params).map_err(|e| rpc::Error::invalid_params(&e.to_string()))
+}
+
+fn to_result<R: Serialize>(response: R) -> Result<serde_json::Value, rpc::Error> {
+    serde_json::to_value(response).map_err(rpc::Error::server_error)
+}
+
+fn serialize_response(id: rpc::Id, result: Result<serde_json::Value, rpc::Error>) -> String {
+    let wrapper = match result {
+        Ok(result) => response::Wrapper::new_with_id(id, Some(result), None),
+        Err(error) => response::Wrapper::new_with_id(id, None, Some(error)),
+    };
+
+    serde_json::to_string_pretty(&wrapper).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use tendermint::rpc::{error::Code, Response};
+    use tendermint::validator;
+    use tendermint_light_client::store::memory::MemoryStore;
+    use tendermint_light_client::types::LightBlock;
+
+    fn read_json_fixture(name: &str) -> String {
+        fs::read_to_string(
+            PathBuf::from("../tendermint/tests/support/rpc/").join(name.to_owned() + ".json"),
+        )
+        .unwrap()
+    }
+
+    fn light_block() -> LightBlock {
+        let commit = commit::Response::from_string(&read_json_fixture("commit")).unwrap();
+        let validators =
+            validators::Response::from_string(&read_json_fixture("validators")).unwrap();
+        let validators = validator::Set::new(validators.validators);
+
+        LightBlock::new(
+            commit.signed_header,
+            validators.clone(),
+            validators,
+            "BADFADAD0BEFEEDC0C0ADEADBEEFC0FFEEFACADE".parse().unwrap(),
+        )
+    }
+
+    // light block of the fixtures, at the given height
+    fn light_block_at(height: u64) -> LightBlock {
+        let mut light_block = light_block();
+        light_block.signed_header.header.height = height.into();
+        light_block
+    }
+
+    fn handler() -> StoreHandler<MemoryStore> {
+        let mut store = MemoryStore::new();
+        store.insert(light_block(), VerifiedStatus::Verified);
+
+        StoreHandler::new(store)
+    }
+
+    #[test]
+    fn latest_commit() {
+        let request = r#"{"jsonrpc": "2.0", "id": 1, "method": "commit", "params": {}}"#;
+        let response = commit::Response::from_string(handle(&handler(), request.as_bytes()))
+            .expect("latest commit");
+
+        assert_eq!(response.signed_header.header.height.value(), 10);
+    }
+
+    #[test]
+    fn latest_trusted_height() {
+        let mut store = MemoryStore::new();
+        store.insert(light_block_at(5), VerifiedStatus::Verified);
+        store.insert(light_block_at(8), VerifiedStatus::Verified);
+        store.insert(light_block_at(12), VerifiedStatus::Unverified);
+        let handler = StoreHandler::new(store);
+
+        // Unverified light blocks are not trusted
+        let request = r#"{"jsonrpc": "2.0", "id": 1, "method": "commit", "params": {}}"#;
+        let response = commit::Response::from_string(handle(&handler, request.as_bytes()))
+            .expect("latest trusted commit");
+
+        assert_eq!(response.signed_header.header.height.value(), 8);
+
+        let handler = StoreHandler::new(MemoryStore::new());
+        let error = commit::Response::from_string(handle(&handler, request.as_bytes()))
+            .expect_err("no trusted commit");
+
+        assert_eq!(error.code(), Code::ServerError);
+    }
+
+    #[test]
+    fn validators_at_next_height() {
+        let request =
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "validators", "params": {"height": "11"}}"#;
+        let response = validators::Response::from_string(handle(&handler(), request.as_bytes()))
+            .expect("validators at height 11");
+
+        assert_eq!(response.block_height.value(), 11);
+        assert_eq!(response.validators.len(), 30);
+        assert_eq!(response.count, 30);
+        assert_eq!(response.total, 65);
+    }
+
+    #[test]
+    fn paginated_validators() {
+        let request = r#"{"jsonrpc": "2.0", "id": 1, "method": "validators", "params": {"height": "10", "page": "3", "per_page": "30"}}"#;
+        let response = validators::Response::from_string(handle(&handler(), request.as_bytes()))
+            .expect("last page of validators");
+
+        assert_eq!(response.count, 5);
+        assert_eq!(response.total, 65);
+        assert_eq!(response.validators.len(), 5);
+
+        let request = r#"{"jsonrpc": "2.0", "id": 1, "method": "validators", "params": {"height": "10", "page": "4", "per_page": "30"}}"#;
+        let error = validators::Response::from_string(handle(&handler(), request.as_bytes()))
+            .expect_err("no fourth page of validators");
+
+        assert_eq!(error.code(), Code::InvalidParams);
+    }
+
+    #[test]
+    fn unverified_height() {
+        let request =
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "commit", "params": {"height": "42"}}"#;
+        let error = commit::Response::from_string(handle(&handler(), request.as_bytes()))
+            .expect_err("no verified block at height 42");
+
+        assert_eq!(error.code(), Code::ServerError);
+
+        let request =
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "validators", "params": {"height": "42"}}"#;
+        let error = validators::Response::from_string(handle(&handler(), request.as_bytes()))
+            .expect_err("no verified validator set at height 42");
+
+        assert_eq!(error.code(), Code::ServerError);
+    }
+
+    #[test]
+    fn unsupported_method() {
+        let request = r#"{"jsonrpc": "2.0", "id": 1, "method": "status", "params": {}}"#;
+        let error = commit::Response::from_string(handle(&handler(), request.as_bytes()))
+            .expect_err("unsupported method");
+
+        assert_eq!(error.code(), Code::MethodNotFound);
+    }
+}
@@ -59,8 +59,8 @@ fn start_with_args() {
 #[ignore]
 fn start_with_config_no_args() {
     let mut config = LightNodeConfig::default();
-    config.rpc_address = "localhost:26657".to_owned();
-    let expected_line = format!("Requesting from {}.", &config.rpc_address);
+    config.primary.address = "tcp://localhost:26657".parse().unwrap();
+    let expected_line = format!("Requesting from {}.", &config.primary.address);
 
     let mut runner = RUNNER.clone();
     let mut cmd = runner.config(&config).arg("start").capture_stdout().run();
@@ -73,7 +73,7 @@ fn start_with_config_no_args() {
 #[ignore]
 fn start_with_config_and_args() {
     let mut config = LightNodeConfig::default();
-    config.rpc_address = "localhost:26657".to_owned();
+    config.primary.address = "tcp://localhost:26657".parse().unwrap();
 
     let mut runner = RUNNER.clone();
     let mut cmd = runner
@@ -82,7 +82,7 @@ fn start_with_config_and_args() {
         .capture_stdout()
         .run();
 
-    cmd.stdout().expect_line("Requesting from other:26657.");
+    cmd.stdout().expect_line("Requesting from tcp://other:26657.");
     cmd.wait().unwrap().expect_success();
 }
 
@@ -8,12 +8,13 @@
 //! <https://github.com/tendermint/rust-abci>
 
 mod code;
-mod data;
+pub(crate) mod data;
 mod gas;
 mod info;
 mod log;
 mod path;
 pub mod responses;
+pub mod results;
 pub mod tag;
 pub mod transaction;
 
@@ -25,5 +26,6 @@ pub use self::{
     log::Log,
     path::Path,
     responses::{DeliverTx, Event, Responses},
+    results::{AbciResult, AbciResults},
     transaction::Transaction,
 };
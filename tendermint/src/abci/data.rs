@@ -35,6 +35,12 @@ impl Display for Data {
     }
 }
 
+impl From<Vec<u8>> for Data {
+    fn from(bytes: Vec<u8>) -> Data {
+        Data(bytes)
+    }
+}
+
 impl FromStr for Data {
     type Err = Error;
 
@@ -62,3 +68,21 @@ impl Serialize for Data {
         self.to_string().serialize(serializer)
     }
 }
+
+/// Serialize `Data` as base64 rather than hex, as in the `[]byte` fields of
+/// ABCI responses
+pub(crate) mod base64 {
+    use super::Data;
+    use crate::serializers;
+    use serde::{Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(data: &Data, serializer: S) -> Result<S::Ok, S::Error> {
+        serializers::bytes::base64string::serialize(data, serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Data, D::Error> {
+        serializers::bytes::base64string::deserialize(deserializer).map(Data)
+    }
+}
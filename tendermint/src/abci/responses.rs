@@ -50,6 +50,7 @@ pub struct DeliverTx {
     pub code: Code,
 
     /// ABCI application data
+    #[serde(default, with = "super::data::base64")]
     pub data: Data,
 
     /// ABCI log data (nondeterministic)
@@ -126,7 +127,7 @@ where
 }
 
 /// Codespace
-#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Codespace(String);
 
 impl AsRef<str> for Codespace {
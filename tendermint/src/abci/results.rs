@@ -0,0 +1,216 @@
+//! Deterministic results of the transactions of a block, as committed to by
+//! the `last_results_hash` of the header of the next block
+
+use super::{Code, Data, DeliverTx};
+use crate::{
+    amino_types::{self, message::AminoMessage},
+    block,
+    error::{Error, Kind},
+    merkle::{simple_hash_from_byte_vectors, simple_proofs_from_byte_vectors, SimpleProof},
+    Hash,
+};
+use anomaly::fail;
+use std::slice;
+
+/// Deterministic part of a `DeliverTx` result, ie. its code and data.
+///
+/// Only the code and data are committed to by the headers, and thus proven by
+/// a `ResultProof`. In particular, the gas wanted and used by a transaction
+/// are not, and neither are its logs and events: they must be trusted from
+/// the node which returned them.
+///
+/// <https://github.com/tendermint/tendermint/blob/v0.33.5/types/results.go>
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AbciResult {
+    /// ABCI application response code
+    pub code: Code,
+
+    /// ABCI application data
+    pub data: Data,
+}
+
+impl AbciResult {
+    /// Amino encoding of this result, ie. the leaf of the results Merkle tree
+    pub fn to_amino_bytes(&self) -> Vec<u8> {
+        amino_types::abci_result::AbciResult::from(self).bytes_vec()
+    }
+}
+
+impl From<&DeliverTx> for AbciResult {
+    fn from(deliver_tx: &DeliverTx) -> Self {
+        AbciResult {
+            code: deliver_tx.code,
+            data: deliver_tx.data.clone(),
+        }
+    }
+}
+
+/// Deterministic results of the transactions of a block, in order
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AbciResults(Vec<AbciResult>);
+
+impl AbciResults {
+    /// Create a new collection of results
+    pub fn new<I>(into_results: I) -> Self
+    where
+        I: Into<Vec<AbciResult>>,
+    {
+        Self(into_results.into())
+    }
+
+    /// Iterate over the results
+    pub fn iter(&self) -> slice::Iter<'_, AbciResult> {
+        self.0.iter()
+    }
+
+    /// Compute the simple Merkle root of the results, ie. the
+    /// `last_results_hash` of the header of the next block, or `None` if
+    /// there's no result
+    pub fn hash(&self) -> Option<Hash> {
+        if self.0.is_empty() {
+            return None;
+        }
+
+        Some(Hash::Sha256(simple_hash_from_byte_vectors(self.leaves())))
+    }
+
+    /// Verify the results against the `last_results_hash` of the header of
+    /// the next block
+    pub fn verify(&self, next_header: &block::Header) -> Result<(), Error> {
+        let hash = self.hash();
+
+        if hash != next_header.last_results_hash {
+            let describe = |hash: Option<Hash>| hash.map_or("empty".to_owned(), |h| h.to_string());
+
+            fail!(
+                Kind::InvalidBlock,
+                "last_results_hash mismatch: header {} has {}, results hash to {}",
+                next_header.height,
+                describe(next_header.last_results_hash),
+                describe(hash)
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Get a proof of the inclusion of the result at the given index, or
+    /// `None` if there's no such result
+    pub fn proof(&self, index: usize) -> Option<ResultProof> {
+        let result = self.0.get(index)?.clone();
+        let (root_hash, mut proofs) = simple_proofs_from_byte_vectors(self.leaves());
+
+        Some(ResultProof {
+            root_hash: Hash::Sha256(root_hash),
+            result,
+            proof: proofs.swap_remove(index),
+        })
+    }
+
+    fn leaves(&self) -> Vec<Vec<u8>> {
+        self.iter().map(AbciResult::to_amino_bytes).collect()
+    }
+}
+
+impl AsRef<[AbciResult]> for AbciResults {
+    fn as_ref(&self) -> &[AbciResult] {
+        &self.0
+    }
+}
+
+impl<'a> From<&'a [DeliverTx]> for AbciResults {
+    fn from(deliver_txs: &'a [DeliverTx]) -> Self {
+        Self(deliver_txs.iter().map(AbciResult::from).collect())
+    }
+}
+
+/// Proof that a transaction result is included in the results of a block, ie.
+/// that its encoding is a leaf of the Merkle tree whose root is the
+/// `last_results_hash` of the header of the next block.
+///
+/// This proves the code and data of the result, but not its gas.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ResultProof {
+    /// Merkle root of the results of the block
+    pub root_hash: Hash,
+
+    /// Proven result
+    pub result: AbciResult,
+
+    /// Inclusion proof of the encoding of the result
+    pub proof: SimpleProof,
+}
+
+impl ResultProof {
+    /// Validate the proof against the `last_results_hash` of a block header
+    pub fn validate(&self, last_results_hash: &Hash) -> Result<(), Error> {
+        if &self.root_hash != last_results_hash {
+            fail!(
+                Kind::InvalidProof,
+                "proof root hash {} does not match results hash {}",
+                self.root_hash,
+                last_results_hash
+            );
+        }
+
+        let Hash::Sha256(root_hash) = self.root_hash;
+
+        self.proof.verify(&root_hash, &self.result.to_amino_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results() -> AbciResults {
+        AbciResults::new(vec![
+            AbciResult::default(),
+            AbciResult {
+                code: Code::Ok,
+                data: Data::from(b"one".to_vec()),
+            },
+            AbciResult {
+                code: Code::from(14),
+                data: Data::default(),
+            },
+            AbciResult {
+                code: Code::from(14),
+                data: Data::from(b"foo".to_vec()),
+            },
+            AbciResult {
+                code: Code::from(14),
+                data: Data::from(b"bar".to_vec()),
+            },
+        ])
+    }
+
+    #[test]
+    fn amino_bytes() {
+        assert!(AbciResult::default().to_amino_bytes().is_empty());
+        assert_eq!(
+            results().0[3].to_amino_bytes(),
+            vec![0x08, 0x0e, 0x12, 0x03, b'f', b'o', b'o']
+        );
+    }
+
+    #[test]
+    fn result_proofs() {
+        let results = results();
+        let root_hash = results.hash().unwrap();
+
+        for (index, result) in results.iter().enumerate() {
+            let proof = results.proof(index).unwrap();
+            assert_eq!(&proof.result, result);
+            proof.validate(&root_hash).unwrap();
+
+            // Other results don't match the proof
+            let mut forged = proof.clone();
+            forged.result.data = Data::from(b"forged".to_vec());
+            assert!(forged.validate(&root_hash).is_err());
+        }
+
+        assert!(results.proof(5).is_none());
+        assert!(AbciResults::default().hash().is_none());
+    }
+}
@@ -1,11 +1,14 @@
 //! Transactions
 
 mod hash;
+mod proof;
 
-pub use self::hash::Hash;
+pub use self::{hash::Hash, proof::Proof};
+use crate::merkle::simple_hash_from_byte_vectors;
 use std::slice;
 use {
     serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer},
+    sha2::{Digest, Sha256},
     subtle_encoding::base64,
 };
 
@@ -86,6 +89,23 @@ impl Data {
     pub fn iter(&self) -> slice::Iter<'_, Transaction> {
         self.as_ref().iter()
     }
+
+    /// Compute the simple Merkle root of the hashes of the transactions, ie.
+    /// the `data_hash` of the block header, or `None` if there's no
+    /// transaction
+    pub fn hash(&self) -> Option<crate::Hash> {
+        if self.as_ref().is_empty() {
+            return None;
+        }
+
+        let tx_hashes = self
+            .iter()
+            .map(|tx| Sha256::digest(tx.as_bytes()).to_vec())
+            .collect();
+        Some(crate::Hash::Sha256(simple_hash_from_byte_vectors(
+            tx_hashes,
+        )))
+    }
 }
 
 impl AsRef<[Transaction]> for Data {
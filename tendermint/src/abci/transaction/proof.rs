@@ -0,0 +1,46 @@
+//! Transaction inclusion proofs
+
+use super::Transaction;
+use crate::{
+    error::{Error, Kind},
+    merkle::SimpleProof,
+    Hash,
+};
+use anomaly::fail;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Proof that a transaction is included in the data of a block, ie. that its
+/// hash is a leaf of the Merkle tree whose root is the block's `data_hash`.
+///
+/// <https://github.com/tendermint/tendermint/blob/v0.33.5/types/tx.go#L79>
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Proof {
+    /// Merkle root of the transactions of the block
+    pub root_hash: Hash,
+
+    /// Proven transaction
+    pub data: Transaction,
+
+    /// Inclusion proof of the hash of the transaction
+    pub proof: SimpleProof,
+}
+
+impl Proof {
+    /// Validate the proof against the `data_hash` of a block header
+    pub fn validate(&self, data_hash: &Hash) -> Result<(), Error> {
+        if &self.root_hash != data_hash {
+            fail!(
+                Kind::InvalidProof,
+                "proof root hash {} does not match data hash {}",
+                self.root_hash,
+                data_hash
+            );
+        }
+
+        let Hash::Sha256(root_hash) = self.root_hash;
+
+        self.proof
+            .verify(&root_hash, &Sha256::digest(self.data.as_bytes()))
+    }
+}
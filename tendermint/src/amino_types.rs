@@ -3,8 +3,12 @@
 
 #![allow(missing_docs)]
 
+pub mod abci_result;
 pub mod block_id;
+pub mod block_params;
+pub mod commit_sig;
 pub mod ed25519;
+pub mod evidence;
 pub mod message;
 pub mod ping;
 pub mod proposal;
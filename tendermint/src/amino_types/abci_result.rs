@@ -0,0 +1,22 @@
+//! Deterministic results of transactions, as hashed into the
+//! `last_results_hash` of headers
+
+use crate::abci;
+use prost_amino_derive::Message;
+
+#[derive(Clone, PartialEq, Message)]
+pub struct AbciResult {
+    #[prost_amino(uint32, tag = "1")]
+    pub code: u32,
+    #[prost_amino(bytes)]
+    pub data: Vec<u8>,
+}
+
+impl From<&abci::AbciResult> for AbciResult {
+    fn from(result: &abci::AbciResult) -> Self {
+        AbciResult {
+            code: result.code.value(),
+            data: result.data.as_bytes().to_vec(),
+        }
+    }
+}
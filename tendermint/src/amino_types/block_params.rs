@@ -0,0 +1,21 @@
+//! Block size parameters, as hashed into the `consensus_hash` of headers
+
+use crate::block;
+use prost_amino_derive::Message;
+
+#[derive(Clone, PartialEq, Message)]
+pub struct BlockParams {
+    #[prost_amino(int64, tag = "1")]
+    pub max_bytes: i64,
+    #[prost_amino(int64)]
+    pub max_gas: i64,
+}
+
+impl From<&block::Size> for BlockParams {
+    fn from(size: &block::Size) -> Self {
+        BlockParams {
+            max_bytes: size.max_bytes as i64,
+            max_gas: size.max_gas,
+        }
+    }
+}
@@ -0,0 +1,33 @@
+//! Evidence of malfeasance, as hashed into the `evidence_hash` of headers
+
+use super::{compute_prefix, vote::Vote};
+use crate::evidence;
+use once_cell::sync::Lazy;
+use prost_amino_derive::Message;
+
+pub const DUPLICATE_VOTE_AMINO_NAME: &str = "tendermint/DuplicateVoteEvidence";
+pub static DUPLICATE_VOTE_AMINO_PREFIX: Lazy<Vec<u8>> =
+    Lazy::new(|| compute_prefix(DUPLICATE_VOTE_AMINO_NAME));
+
+// Note: the public key is an interface on the golang side, encoded with the
+// prefix of its concrete type (see `PublicKey::to_amino_bytes`). The prefix
+// of the evidence type itself is not included: see `evidence::Evidence`.
+#[derive(Clone, PartialEq, Message)]
+pub struct DuplicateVoteEvidence {
+    #[prost_amino(bytes, tag = "1")]
+    pub pub_key: Vec<u8>,
+    #[prost_amino(message)]
+    pub vote_a: Option<Vote>,
+    #[prost_amino(message)]
+    pub vote_b: Option<Vote>,
+}
+
+impl From<&evidence::DuplicateVoteEvidence> for DuplicateVoteEvidence {
+    fn from(evidence: &evidence::DuplicateVoteEvidence) -> Self {
+        DuplicateVoteEvidence {
+            pub_key: evidence.pub_key.to_amino_bytes(),
+            vote_a: Some(Vote::from(&evidence.vote_a)),
+            vote_b: Some(Vote::from(&evidence.vote_b)),
+        }
+    }
+}
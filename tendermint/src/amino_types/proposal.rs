@@ -15,7 +15,7 @@ use crate::{
 };
 use bytes::BufMut;
 use once_cell::sync::Lazy;
-use prost_amino::{EncodeError, Message};
+use prost_amino::{DecodeError, EncodeError, Message};
 use prost_amino_derive::Message;
 use signatory::ed25519;
 use std::convert::TryFrom;
@@ -135,6 +135,14 @@ impl SignableMsg for SignProposalRequest {
             prop.signature = sig.as_ref().to_vec();
         }
     }
+    fn set_timestamp(&mut self, timestamp: TimeMsg) {
+        if let Some(ref mut prop) = self.proposal {
+            prop.timestamp = Some(timestamp);
+        }
+    }
+    fn parse_sign_bytes_timestamp(sign_bytes: &[u8]) -> Result<Option<TimeMsg>, DecodeError> {
+        Ok(CanonicalProposal::decode_length_delimited(sign_bytes)?.timestamp)
+    }
     fn validate(&self) -> Result<(), validate::Error> {
         match self.proposal {
             Some(ref p) => p.validate_basic(),
@@ -149,7 +157,7 @@ impl SignableMsg for SignProposalRequest {
                     Err(_err) => return None, // TODO(tarcieri): return an error?
                 },
                 round: p.round,
-                step: 3,
+                step: 1,
                 block_id: {
                     match p.block_id {
                         Some(ref b) => match b.parse_block_id() {
@@ -22,6 +22,14 @@ pub enum RemoteErrorCode {
 }
 
 impl RemoteError {
+    /// Create a new generic remote signer error with the given description
+    pub fn signer_error(description: impl Into<String>) -> Self {
+        RemoteError {
+            code: RemoteErrorCode::RemoteSignerError as i32,
+            description: description.into(),
+        }
+    }
+
     /// Create a new double signing error with the given message
     pub fn double_sign(height: i64) -> Self {
         RemoteError {
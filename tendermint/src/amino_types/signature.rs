@@ -1,4 +1,4 @@
-use super::validate;
+use super::{time::TimeMsg, validate};
 use crate::{chain, consensus};
 use bytes::BufMut;
 use prost_amino::{DecodeError, EncodeError};
@@ -15,6 +15,13 @@ pub trait SignableMsg {
 
     /// Set the Ed25519 signature on the underlying message
     fn set_signature(&mut self, sig: &ed25519::Signature);
+
+    /// Set the timestamp of the underlying message
+    fn set_timestamp(&mut self, timestamp: TimeMsg);
+
+    /// Parse the timestamp of a message of this type from its sign bytes
+    fn parse_sign_bytes_timestamp(sign_bytes: &[u8]) -> Result<Option<TimeMsg>, DecodeError>;
+
     fn validate(&self) -> Result<(), validate::Error>;
     fn consensus_state(&self) -> Option<consensus::State>;
     fn height(&self) -> Option<i64>;
@@ -17,7 +17,7 @@ use crate::{
 };
 use bytes::BufMut;
 use once_cell::sync::Lazy;
-use prost_amino::{error::EncodeError, Message};
+use prost_amino::{error::EncodeError, DecodeError, Message};
 use prost_amino_derive::Message;
 use signatory::ed25519;
 use std::convert::TryFrom;
@@ -179,6 +179,14 @@ impl SignableMsg for SignVoteRequest {
             vt.signature = sig.as_ref().to_vec();
         }
     }
+    fn set_timestamp(&mut self, timestamp: TimeMsg) {
+        if let Some(ref mut vt) = self.vote {
+            vt.timestamp = Some(timestamp);
+        }
+    }
+    fn parse_sign_bytes_timestamp(sign_bytes: &[u8]) -> Result<Option<TimeMsg>, DecodeError> {
+        Ok(CanonicalVote::decode_length_delimited(sign_bytes)?.timestamp)
+    }
     fn validate(&self) -> Result<(), validate::Error> {
         match self.vote {
             Some(ref v) => v.validate_basic(),
@@ -193,7 +201,11 @@ impl SignableMsg for SignVoteRequest {
                     Err(_err) => return None, // TODO(tarcieri): return an error?
                 },
                 round: v.round,
-                step: 6,
+                step: match v.msg_type()? {
+                    SignedMsgType::PreVote => 2,
+                    SignedMsgType::PreCommit => 3,
+                    SignedMsgType::Proposal => return None,
+                },
                 block_id: {
                     match v.block_id {
                         Some(ref b) => match b.parse_block_id() {
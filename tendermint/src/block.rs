@@ -19,7 +19,8 @@ pub use self::{
     meta::Meta,
     size::Size,
 };
-use crate::{abci::transaction, evidence, serializers};
+use crate::{abci::transaction, evidence, serializers, validator, Error, Hash, Kind};
+use anomaly::fail;
 use serde::{Deserialize, Deserializer, Serialize};
 
 /// Blocks consist of a header, transactions, votes (the commit), and a list of
@@ -42,6 +43,73 @@ pub struct Block {
     pub last_commit: Option<Commit>,
 }
 
+impl Block {
+    /// Verify that the transactions, evidence and last commit of this block
+    /// hash to the `data_hash`, `evidence_hash` and `last_commit_hash` of its
+    /// header.
+    ///
+    /// Together with a verified header, this authenticates the whole block.
+    pub fn verify_hashes(&self) -> Result<(), Error> {
+        verify_hash("data_hash", self.header.data_hash, self.data.hash())?;
+        verify_hash(
+            "evidence_hash",
+            self.header.evidence_hash,
+            self.evidence.hash(),
+        )?;
+        verify_hash(
+            "last_commit_hash",
+            self.header.last_commit_hash,
+            self.last_commit.as_ref().and_then(Commit::hash),
+        )
+    }
+
+    /// Verify the time of this block against the BFT time computed from its
+    /// last commit, given the validators of the previous block.
+    ///
+    /// The time of the first block is the genesis time, and isn't verified.
+    pub fn verify_time(&self, last_validators: &validator::Set) -> Result<(), Error> {
+        let last_commit = match &self.last_commit {
+            Some(last_commit) => last_commit,
+            None if self.header.height.value() == 1 => return Ok(()),
+            None => fail!(
+                Kind::InvalidBlock,
+                "missing last commit of block {}",
+                self.header.height
+            ),
+        };
+
+        let median_time = last_commit.median_time(last_validators)?;
+
+        if self.header.time != median_time {
+            fail!(
+                Kind::InvalidBlock,
+                "block time {} doesn't match the median time {} of its last commit",
+                self.header.time,
+                median_time
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Check that the hash of a part of a block matches the given header field
+fn verify_hash(field: &str, header_hash: Option<Hash>, hash: Option<Hash>) -> Result<(), Error> {
+    if header_hash != hash {
+        let describe = |hash: Option<Hash>| hash.map_or("empty".to_owned(), |h| h.to_string());
+
+        fail!(
+            Kind::InvalidBlock,
+            "{} mismatch: header has {}, block hashes to {}",
+            field,
+            describe(header_hash),
+            describe(hash)
+        );
+    }
+
+    Ok(())
+}
+
 pub(crate) fn parse_non_empty_commit<'de, D>(deserializer: D) -> Result<Option<Commit>, D::Error>
 where
     D: Deserializer<'de>,
@@ -71,3 +139,102 @@ where
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Block, CommitSig, CommitSigs};
+    use crate::{abci::transaction, evidence, validator, validator::tests::make_validator};
+
+    fn parse_block(response: &str) -> Block {
+        let response: serde_json::Value = serde_json::from_str(response).unwrap();
+        serde_json::from_value(response["result"]["block"].clone()).unwrap()
+    }
+
+    fn block_with_evidences() -> Block {
+        parse_block(include_str!(
+            "../tests/support/rpc/block_with_evidences.json"
+        ))
+    }
+
+    // make a validator with the given address, as found in the last commit of a block
+    fn last_validator(address: &str, vp: u64) -> validator::Info {
+        validator::Info {
+            address: address.parse().unwrap(),
+            ..make_validator(
+                "F349539C7E5EF7C49549B09C4BFC2335318AB0FE51FBFAA2433B4F13E816F4A7",
+                vp,
+            )
+        }
+    }
+
+    #[test]
+    fn verify_time() {
+        let block = block_with_evidences();
+
+        // The later of the two votes carries most of the voting power
+        let last_validators = validator::Set::new(vec![
+            last_validator("0F1F93CC25A6CFC083F54E4DA26F73B7F24DC85B", 20),
+            last_validator("9642515585D8F27423CE1BDDB91CD076862AFC63", 10),
+        ]);
+        assert_eq!(
+            block
+                .last_commit
+                .as_ref()
+                .unwrap()
+                .median_time(&last_validators)
+                .unwrap(),
+            block.header.time
+        );
+        block.verify_time(&last_validators).unwrap();
+
+        // Absent votes are ignored
+        let mut with_absent_vote = block.clone();
+        let last_commit = with_absent_vote.last_commit.as_mut().unwrap();
+        let mut signatures = last_commit.signatures.clone().into_vec();
+        signatures.push(CommitSig::BlockIDFlagAbsent);
+        last_commit.signatures = CommitSigs::new(signatures);
+        with_absent_vote.verify_time(&last_validators).unwrap();
+
+        // With equal voting powers, the median is the earlier vote
+        let last_validators = validator::Set::new(vec![
+            last_validator("0F1F93CC25A6CFC083F54E4DA26F73B7F24DC85B", 10),
+            last_validator("9642515585D8F27423CE1BDDB91CD076862AFC63", 10),
+        ]);
+        assert!(block.verify_time(&last_validators).is_err());
+
+        // Votes of unknown validators are rejected
+        let last_validators = validator::Set::new(vec![last_validator(
+            "0F1F93CC25A6CFC083F54E4DA26F73B7F24DC85B",
+            1,
+        )]);
+        let error = block.verify_time(&last_validators).unwrap_err().to_string();
+        assert!(error.contains("unknown validator"), "{}", error);
+    }
+
+    #[test]
+    fn verify_hashes() {
+        block_with_evidences().verify_hashes().unwrap();
+        parse_block(include_str!("../tests/support/rpc/block.json"))
+            .verify_hashes()
+            .unwrap();
+        parse_block(include_str!("../tests/support/rpc/first_block.json"))
+            .verify_hashes()
+            .unwrap();
+
+        let mut block = block_with_evidences();
+        block.data =
+            transaction::Data::new(vec![transaction::Transaction::new(b"name=eve".to_vec())]);
+        let error = block.verify_hashes().unwrap_err().to_string();
+        assert!(error.contains("data_hash"), "{}", error);
+
+        let mut block = block_with_evidences();
+        block.evidence = evidence::Data::default();
+        let error = block.verify_hashes().unwrap_err().to_string();
+        assert!(error.contains("evidence_hash"), "{}", error);
+
+        let mut block = block_with_evidences();
+        block.last_commit = None;
+        let error = block.verify_hashes().unwrap_err().to_string();
+        assert!(error.contains("last_commit_hash"), "{}", error);
+    }
+}
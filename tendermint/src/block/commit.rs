@@ -1,8 +1,11 @@
 //! Commits to a Tendermint blockchain
 
+use crate::amino_types::{self, message::AminoMessage};
 use crate::block::commit_sig::CommitSig;
 use crate::block::{Height, Id};
-use crate::serializers;
+use crate::merkle::simple_hash_from_byte_vectors;
+use crate::{serializers, validator, Error, Hash, Kind, Time};
+use anomaly::{fail, format_err};
 use serde::{Deserialize, Serialize};
 use std::{ops::Deref, slice};
 
@@ -27,6 +30,82 @@ pub struct Commit {
     pub signatures: CommitSigs,
 }
 
+impl Commit {
+    /// Compute the simple Merkle root of the signatures of this commit, ie.
+    /// the `last_commit_hash` of the header of the next block, or `None` if
+    /// there's no signature
+    pub fn hash(&self) -> Option<Hash> {
+        if self.signatures.is_empty() {
+            return None;
+        }
+
+        let signature_bytes = self
+            .signatures
+            .iter()
+            .map(|commit_sig| amino_types::commit_sig::CommitSig::from(commit_sig).bytes_vec())
+            .collect();
+        Some(Hash::Sha256(simple_hash_from_byte_vectors(signature_bytes)))
+    }
+
+    /// Compute the BFT time of the block following this commit: the median of
+    /// the timestamps of the votes, weighted by the voting power of their
+    /// validators in the given set, ie. the validators of the committed block.
+    ///
+    /// Absent votes are ignored. Fails if a vote is from a validator missing
+    /// from the set, or if there's no vote at all.
+    ///
+    /// <https://github.com/tendermint/tendermint/blob/master/docs/spec/consensus/bft-time.md>
+    pub fn median_time(&self, validators: &validator::Set) -> Result<Time, Error> {
+        let mut weighted_times = vec![];
+
+        for commit_sig in self.signatures.iter() {
+            let (validator_address, timestamp) = match commit_sig {
+                CommitSig::BlockIDFlagAbsent => continue,
+                CommitSig::BlockIDFlagCommit {
+                    validator_address,
+                    timestamp,
+                    ..
+                }
+                | CommitSig::BlockIDFlagNil {
+                    validator_address,
+                    timestamp,
+                    ..
+                } => (validator_address, timestamp),
+            };
+
+            let validator = validators.validator(*validator_address).ok_or_else(|| {
+                format_err!(
+                    Kind::InvalidBlock,
+                    "vote of unknown validator {} in commit of block {}",
+                    validator_address,
+                    self.height
+                )
+            })?;
+
+            weighted_times.push((*timestamp, validator.power()));
+        }
+
+        let total_power: u64 = weighted_times.iter().map(|(_, power)| power).sum();
+        weighted_times.sort_by_key(|(timestamp, _)| *timestamp);
+
+        let mut median = total_power / 2;
+
+        for (timestamp, power) in weighted_times {
+            if median <= power {
+                return Ok(timestamp);
+            }
+
+            median -= power;
+        }
+
+        fail!(
+            Kind::InvalidBlock,
+            "no vote in commit of block {}",
+            self.height
+        )
+    }
+}
+
 /// CommitSigs which certify that a block is valid
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct CommitSigs(Vec<CommitSig>);
@@ -125,13 +125,15 @@ impl TendermintConfig {
         Self::parse_toml(toml_string)
     }
 
-    /// Load `genesis.json` file from the configured location
+    /// Load `genesis.json` file from the configured location, and validate it
     pub fn load_genesis_file(&self, home: impl AsRef<Path>) -> Result<Genesis, Error> {
         let path = home.as_ref().join(&self.genesis_file);
         let genesis_json = fs::read_to_string(&path)
             .map_err(|e| format_err!(Kind::Parse, "couldn't open {}: {}", path.display(), e))?;
 
-        Ok(serde_json::from_str(genesis_json.as_ref())?)
+        let genesis: Genesis = serde_json::from_str(genesis_json.as_ref())?;
+        genesis.validate()?;
+        Ok(genesis)
     }
 
     /// Load `node_key.json` file from the configured location
@@ -1,6 +1,7 @@
 //! Tendermint consensus
 
 pub mod params;
+pub mod round_step;
 pub mod state;
 
-pub use self::{params::Params, state::State};
+pub use self::{params::Params, round_step::RoundStep, state::State};
@@ -1,7 +1,19 @@
 //! Tendermint consensus parameters
 
-use crate::{block, evidence, public_key};
+use crate::{
+    amino_types::{block_params::BlockParams, message::AminoMessage},
+    block,
+    error::{Error, Kind},
+    evidence, public_key, Hash,
+};
+use anomaly::fail;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+/// Maximum size of blocks, in bytes. Matches `MaxBlockSizeBytes` from:
+/// <https://github.com/tendermint/tendermint/blob/v0.33.5/types/params.go>
+pub const MAX_BLOCK_SIZE_BYTES: u64 = 104_857_600;
 
 /// Tendermint consensus parameters
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
@@ -16,9 +28,158 @@ pub struct Params {
     pub validator: ValidatorParams,
 }
 
+impl Params {
+    /// Check that these parameters are sane, as Tendermint requires of those
+    /// of a genesis file
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.block.max_bytes == 0 || self.block.max_bytes > MAX_BLOCK_SIZE_BYTES {
+            fail!(
+                Kind::OutOfRange,
+                "block.max_bytes must be between 1 and {}, got {}",
+                MAX_BLOCK_SIZE_BYTES,
+                self.block.max_bytes
+            );
+        }
+
+        if self.block.max_gas < -1 {
+            fail!(
+                Kind::OutOfRange,
+                "block.max_gas must be greater or equal to -1, got {}",
+                self.block.max_gas
+            );
+        }
+
+        if self.evidence.max_age_num_blocks == 0 {
+            fail!(
+                Kind::OutOfRange,
+                "evidence.max_age_num_blocks must be greater than 0"
+            );
+        }
+
+        if Duration::from(self.evidence.max_age_duration) == Duration::default() {
+            fail!(
+                Kind::OutOfRange,
+                "evidence.max_age_duration must be greater than 0"
+            );
+        }
+
+        if self.validator.pub_key_types.is_empty() {
+            fail!(
+                Kind::OutOfRange,
+                "validator.pub_key_types must contain at least one key type"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Compute the hash of these parameters, ie. the `consensus_hash` of the
+    /// headers of the blocks they apply to.
+    ///
+    /// Only the maximum size and gas of blocks are hashed, so that the other
+    /// parameters can evolve without breaking the block protocol.
+    ///
+    /// <https://github.com/tendermint/tendermint/blob/v0.33.5/types/params.go>
+    pub fn hash(&self) -> Hash {
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(&Sha256::digest(&BlockParams::from(&self.block).bytes_vec()));
+        Hash::Sha256(hash)
+    }
+
+    /// Verify these parameters against the `consensus_hash` of a (verified)
+    /// header
+    pub fn verify(&self, header: &block::Header) -> Result<(), Error> {
+        let hash = self.hash();
+
+        if hash != header.consensus_hash {
+            fail!(
+                Kind::InvalidBlock,
+                "consensus_hash mismatch: header {} has {}, params hash to {}",
+                header.height,
+                header.consensus_hash,
+                hash
+            );
+        }
+
+        Ok(())
+    }
+}
+
 /// Validator consensus parameters
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct ValidatorParams {
     /// Allowed algorithms for validator signing
     pub pub_key_types: Vec<public_key::Algorithm>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::Algorithm;
+
+    fn params(max_bytes: u64, max_gas: i64) -> Params {
+        serde_json::from_value(serde_json::json!({
+            "block": {
+                "max_bytes": max_bytes.to_string(),
+                "max_gas": max_gas.to_string(),
+                "time_iota_ms": "1000"
+            },
+            "evidence": {
+                "max_age_num_blocks": "100000",
+                "max_age_duration": "172800000000000"
+            },
+            "validator": {
+                "pub_key_types": ["ed25519"]
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn validate() {
+        params(22_020_096, -1).validate().unwrap();
+        params(MAX_BLOCK_SIZE_BYTES, 0).validate().unwrap();
+
+        assert!(params(0, -1).validate().is_err());
+        assert!(params(MAX_BLOCK_SIZE_BYTES + 1, -1).validate().is_err());
+        assert!(params(22_020_096, -2).validate().is_err());
+
+        let mut no_key_types = params(22_020_096, -1);
+        no_key_types.validator.pub_key_types.clear();
+        assert!(no_key_types.validate().is_err());
+    }
+
+    #[test]
+    fn hash() {
+        // Default parameters of Tendermint v0.33
+        assert_eq!(
+            params(22_020_096, -1).hash(),
+            Hash::from_hex_upper(
+                Algorithm::Sha256,
+                "048091BC7DDC283F77BFBF91D73C44DA58C3DF8A9CBC867405D8B7F3DAADA22F"
+            )
+            .unwrap()
+        );
+
+        // Parameters of cosmoshub-2
+        assert_eq!(
+            params(150_000, 1_500_000).hash(),
+            Hash::from_hex_upper(
+                Algorithm::Sha256,
+                "29C5629148426FB74676BE07F40F2ED79674A67F5833E4C9CCBF759C9372E99C"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn verify() {
+        let response: serde_json::Value =
+            serde_json::from_str(include_str!("../../tests/support/rpc/block.json")).unwrap();
+        let header: block::Header =
+            serde_json::from_value(response["result"]["block"]["header"].clone()).unwrap();
+
+        params(22_020_096, -1).verify(&header).unwrap();
+        assert!(params(22_020_096, 10_000_000).verify(&header).is_err());
+    }
+}
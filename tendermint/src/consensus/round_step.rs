@@ -0,0 +1,111 @@
+//! Steps of a consensus round
+
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// Step of a consensus round, as reported by a node's consensus reactor.
+/// Matches `RoundStepType` from:
+/// <https://github.com/tendermint/tendermint/blob/v0.33.5/consensus/types/round_state.go>
+///
+/// Not to be confused with the signing step stored in [`consensus::State`],
+/// which only tells proposals, prevotes and precommits apart.
+///
+/// [`consensus::State`]: struct.State.html
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub enum RoundStep {
+    /// Waiting for the commit timeout of the previous height
+    NewHeight = 1,
+
+    /// Setting up a new round
+    NewRound = 2,
+
+    /// Proposing or waiting for the proposal
+    Propose = 3,
+
+    /// Prevoting
+    Prevote = 4,
+
+    /// Waiting for more prevotes after +2/3 of any prevotes
+    PrevoteWait = 5,
+
+    /// Precommitting
+    Precommit = 6,
+
+    /// Waiting for more precommits after +2/3 of any precommits
+    PrecommitWait = 7,
+
+    /// Committing the block, after +2/3 of precommits for it
+    Commit = 8,
+}
+
+impl RoundStep {
+    /// Deserialize this step from a byte
+    pub fn from_u8(byte: u8) -> Option<RoundStep> {
+        match byte {
+            1 => Some(RoundStep::NewHeight),
+            2 => Some(RoundStep::NewRound),
+            3 => Some(RoundStep::Propose),
+            4 => Some(RoundStep::Prevote),
+            5 => Some(RoundStep::PrevoteWait),
+            6 => Some(RoundStep::Precommit),
+            7 => Some(RoundStep::PrecommitWait),
+            8 => Some(RoundStep::Commit),
+            _ => None,
+        }
+    }
+
+    /// Serialize this step as a byte
+    pub fn to_u8(self) -> u8 {
+        self as u8
+    }
+
+    /// Parse this step from its name in round state events, eg.
+    /// `RoundStepPropose`
+    pub fn from_name(name: &str) -> Option<RoundStep> {
+        match name {
+            "RoundStepNewHeight" => Some(RoundStep::NewHeight),
+            "RoundStepNewRound" => Some(RoundStep::NewRound),
+            "RoundStepPropose" => Some(RoundStep::Propose),
+            "RoundStepPrevote" => Some(RoundStep::Prevote),
+            "RoundStepPrevoteWait" => Some(RoundStep::PrevoteWait),
+            "RoundStepPrecommit" => Some(RoundStep::Precommit),
+            "RoundStepPrecommitWait" => Some(RoundStep::PrecommitWait),
+            "RoundStepCommit" => Some(RoundStep::Commit),
+            _ => None,
+        }
+    }
+
+    /// Name of this step in round state events, eg. `RoundStepPropose`
+    pub fn name(self) -> &'static str {
+        match self {
+            RoundStep::NewHeight => "RoundStepNewHeight",
+            RoundStep::NewRound => "RoundStepNewRound",
+            RoundStep::Propose => "RoundStepPropose",
+            RoundStep::Prevote => "RoundStepPrevote",
+            RoundStep::PrevoteWait => "RoundStepPrevoteWait",
+            RoundStep::Precommit => "RoundStepPrecommit",
+            RoundStep::PrecommitWait => "RoundStepPrecommitWait",
+            RoundStep::Commit => "RoundStepCommit",
+        }
+    }
+}
+
+impl fmt::Display for RoundStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_u8())
+    }
+}
+
+impl Serialize for RoundStep {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_u8().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for RoundStep {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let byte = u8::deserialize(deserializer)?;
+        RoundStep::from_u8(byte)
+            .ok_or_else(|| D::Error::custom(format!("invalid round step: {}", byte)))
+    }
+}
@@ -21,7 +21,13 @@ pub struct State {
     #[serde(with = "serializers::from_str")]
     pub round: i64,
 
-    /// Current consensus step
+    /// Current signing step: 1 for proposals, 2 for prevotes and 3 for
+    /// precommits, as tracked by validators to avoid double signing.
+    ///
+    /// Steps of consensus rounds, as reported by nodes, are represented by
+    /// [`RoundStep`] instead.
+    ///
+    /// [`RoundStep`]: enum.RoundStep.html
     pub step: i8,
 
     /// Block ID being proposed (if available)
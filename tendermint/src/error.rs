@@ -40,6 +40,14 @@ pub enum Kind {
     /// Signature invalid
     #[error("bad signature")]
     SignatureInvalid,
+
+    /// Merkle proof invalid
+    #[error("invalid Merkle proof")]
+    InvalidProof,
+
+    /// Block inconsistent with its header, or with the chain
+    #[error("invalid block")]
+    InvalidBlock,
 }
 
 impl Kind {
@@ -2,8 +2,13 @@
 
 use std::slice;
 use {
-    crate::{serializers, PublicKey, Vote},
+    crate::{
+        amino_types::{self, message::AminoMessage},
+        merkle::simple_hash_from_byte_vectors,
+        serializers, Hash, PublicKey, Vote,
+    },
     serde::{Deserialize, Serialize},
+    sha2::{Digest, Sha256},
 };
 
 /// Evidence of malfeasance by validators (i.e. signing conflicting votes).
@@ -19,15 +24,38 @@ pub enum Evidence {
     DuplicateVote(DuplicateVoteEvidence),
 }
 
+impl Evidence {
+    /// Amino encoding of this evidence, with the prefix of its type
+    pub fn to_amino_bytes(&self) -> Vec<u8> {
+        match self {
+            Evidence::DuplicateVote(evidence) => {
+                let mut bytes = amino_types::evidence::DUPLICATE_VOTE_AMINO_PREFIX.clone();
+                bytes.extend(
+                    amino_types::evidence::DuplicateVoteEvidence::from(evidence).bytes_vec(),
+                );
+                bytes
+            }
+        }
+    }
+
+    /// Compute the hash of this evidence, eg. as returned by the
+    /// `/broadcast_evidence` RPC endpoint
+    pub fn hash(&self) -> Hash {
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(&Sha256::digest(&self.to_amino_bytes()));
+        Hash::Sha256(hash)
+    }
+}
+
 /// Duplicate vote evidence
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct DuplicateVoteEvidence {
     #[serde(rename = "PubKey")]
-    pub_key: PublicKey,
+    pub(crate) pub_key: PublicKey,
     #[serde(rename = "VoteA")]
-    vote_a: Vote,
+    pub(crate) vote_a: Vote,
     #[serde(rename = "VoteB")]
-    vote_b: Vote,
+    pub(crate) vote_b: Vote,
 }
 
 /// Evidence data is a wrapper for a list of `Evidence`.
@@ -58,6 +86,17 @@ impl Data {
     pub fn iter(&self) -> slice::Iter<'_, Evidence> {
         self.as_ref().iter()
     }
+
+    /// Compute the simple Merkle root of the evidence, ie. the `evidence_hash`
+    /// of the block header, or `None` if there's no evidence
+    pub fn hash(&self) -> Option<Hash> {
+        if self.as_ref().is_empty() {
+            return None;
+        }
+
+        let evidence_bytes = self.iter().map(Evidence::to_amino_bytes).collect();
+        Some(Hash::Sha256(simple_hash_from_byte_vectors(evidence_bytes)))
+    }
 }
 
 impl AsRef<[Evidence]> for Data {
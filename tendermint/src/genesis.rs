@@ -1,7 +1,14 @@
 //! Genesis data
 
-use crate::{chain, consensus, validator, Time};
+use crate::{
+    account, chain, consensus,
+    error::{Error, Kind},
+    lite::ValidatorSet,
+    validator, Hash, Time,
+};
+use anomaly::fail;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 /// Genesis data
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -26,3 +33,72 @@ pub struct Genesis<AppState = serde_json::Value> {
     #[serde(default)]
     pub app_state: AppState,
 }
+
+impl<AppState> Genesis<AppState> {
+    /// Check that this genesis data can start a chain, as Tendermint's
+    /// `GenesisDoc.ValidateAndComplete` does, except that the initial
+    /// validators can't be left for the application to choose.
+    ///
+    /// Chain IDs are checked against `chain::id::MAX_LENGTH` when parsed, so
+    /// this checks that:
+    ///
+    /// - there is at least one validator
+    /// - each validator has some voting power, and the address of its public
+    ///   key
+    /// - the consensus parameters are sane
+    ///
+    /// <https://github.com/tendermint/tendermint/blob/v0.33.5/types/genesis.go>
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.validators.is_empty() {
+            fail!(Kind::Length, "genesis must contain at least one validator");
+        }
+
+        for validator in &self.validators {
+            if validator.voting_power.is_zero() {
+                fail!(
+                    Kind::OutOfRange,
+                    "validator {} has no voting power",
+                    validator.address
+                );
+            }
+
+            let address = account::Id::from(validator.pub_key);
+
+            if validator.address != address {
+                fail!(
+                    Kind::InvalidKey,
+                    "incorrect address for validator {}, should be {}",
+                    validator.address,
+                    address
+                );
+            }
+        }
+
+        self.consensus_params.validate()
+    }
+
+    /// Initial validator set of the chain
+    pub fn validator_set(&self) -> validator::Set {
+        validator::Set::new(self.validators.clone())
+    }
+
+    /// Hash of the initial validator set, ie. the `validators_hash` of the
+    /// header of the first block, which light clients may trust to bootstrap
+    pub fn validators_hash(&self) -> Hash {
+        self.validator_set().hash()
+    }
+}
+
+impl<AppState: Serialize> Genesis<AppState> {
+    /// Compute the SHA-256 hash of the JSON encoding of this genesis data.
+    ///
+    /// As the encoding doesn't depend on the formatting of the genesis file it
+    /// was parsed from, the hash is stable across reformatted copies of it.
+    pub fn hash(&self) -> Result<Hash, Error> {
+        let genesis_json = serde_json::to_vec(self)?;
+
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(&Sha256::digest(&genesis_json));
+        Ok(Hash::Sha256(hash))
+    }
+}
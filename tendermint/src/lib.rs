@@ -37,8 +37,10 @@ mod moniker;
 pub mod net;
 pub mod node;
 pub mod private_key;
+pub mod privval;
 pub mod public_key;
 pub mod rpc;
+pub mod secret_connection;
 pub mod serializers;
 pub mod signature;
 pub mod time;
@@ -2,6 +2,12 @@
 
 pub mod proof;
 
+use crate::{
+    error::{Error, Kind},
+    serializers,
+};
+use anomaly::fail;
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
 /// Size of Merkle root hash
@@ -32,6 +38,133 @@ fn simple_hash_from_byte_slices_inner(byte_slices: &[Vec<u8>]) -> Hash {
     }
 }
 
+/// Compute a simple Merkle root from vectors of arbitrary byte vectors,
+/// along with an inclusion proof for each of them.
+pub fn simple_proofs_from_byte_vectors(byte_vecs: Vec<Vec<u8>>) -> (Hash, Vec<SimpleProof>) {
+    let total = byte_vecs.len() as u64;
+    let (root, trails) = simple_trails_from_byte_slices(byte_vecs.as_slice());
+
+    let proofs = trails
+        .into_iter()
+        .enumerate()
+        .map(|(index, (leaf_hash, aunts))| SimpleProof {
+            total,
+            index: index as u64,
+            leaf_hash: leaf_hash.to_vec(),
+            aunts,
+        })
+        .collect();
+
+    (root, proofs)
+}
+
+// recurse into subtrees, collecting the leaf hash and aunts of each leaf
+fn simple_trails_from_byte_slices(byte_slices: &[Vec<u8>]) -> (Hash, Vec<(Hash, Vec<Vec<u8>>)>) {
+    let length = byte_slices.len();
+    match length {
+        0 => ([0; HASH_SIZE], vec![]),
+        1 => {
+            let hash = leaf_hash(byte_slices[0].as_slice());
+            (hash, vec![(hash, vec![])])
+        }
+        _ => {
+            let k = get_split_point(length);
+            let (left, mut left_trails) = simple_trails_from_byte_slices(&byte_slices[..k]);
+            let (right, mut right_trails) = simple_trails_from_byte_slices(&byte_slices[k..]);
+
+            for (_, aunts) in left_trails.iter_mut() {
+                aunts.push(right.to_vec());
+            }
+
+            for (_, aunts) in right_trails.iter_mut() {
+                aunts.push(left.to_vec());
+            }
+
+            left_trails.append(&mut right_trails);
+            (inner_hash(&left, &right), left_trails)
+        }
+    }
+}
+
+/// Inclusion proof of a leaf in a simple Merkle tree
+/// <https://github.com/tendermint/tendermint/blob/v0.33.5/crypto/merkle/simple_proof.go>
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SimpleProof {
+    /// Total number of leaves in the tree
+    #[serde(with = "serializers::from_str")]
+    pub total: u64,
+
+    /// Index of the proven leaf
+    #[serde(with = "serializers::from_str")]
+    pub index: u64,
+
+    /// Hash of the proven leaf
+    #[serde(with = "serializers::bytes::base64string")]
+    pub leaf_hash: Vec<u8>,
+
+    /// Hashes of the siblings of the nodes on the path from the leaf
+    /// (included) to the root (excluded), from the bottom up
+    #[serde(with = "serializers::bytes::vec_base64string")]
+    pub aunts: Vec<Vec<u8>>,
+}
+
+impl SimpleProof {
+    /// Verify that `leaf` is included in the tree with the given root hash
+    pub fn verify(&self, root_hash: &Hash, leaf: &[u8]) -> Result<(), Error> {
+        if self.leaf_hash != leaf_hash(leaf) {
+            fail!(Kind::InvalidProof, "invalid leaf hash");
+        }
+
+        match self.compute_root_hash() {
+            Some(hash) if &hash == root_hash => Ok(()),
+            _ => fail!(Kind::InvalidProof, "invalid root hash"),
+        }
+    }
+
+    /// Compute the root hash of the tree from the leaf hash and aunts, or
+    /// `None` if the proof is malformed.
+    pub fn compute_root_hash(&self) -> Option<Hash> {
+        compute_hash_from_aunts(self.index, self.total, &self.leaf_hash, &self.aunts)
+    }
+}
+
+// recurse into the subtree containing the leaf, using the aunts from the root down
+fn compute_hash_from_aunts(
+    index: u64,
+    total: u64,
+    leaf_hash: &[u8],
+    aunts: &[Vec<u8>],
+) -> Option<Hash> {
+    if index >= total {
+        return None;
+    }
+
+    match total {
+        1 => {
+            if !aunts.is_empty() || leaf_hash.len() != HASH_SIZE {
+                return None;
+            }
+
+            let mut hash = [0u8; HASH_SIZE];
+            hash.copy_from_slice(leaf_hash);
+            Some(hash)
+        }
+        _ => {
+            let (last_aunt, aunts) = aunts.split_last()?;
+            let num_left = get_split_point(total as usize) as u64;
+
+            if index < num_left {
+                let left = compute_hash_from_aunts(index, num_left, leaf_hash, aunts)?;
+                Some(inner_hash(&left, last_aunt))
+            } else {
+                let right =
+                    compute_hash_from_aunts(index - num_left, total - num_left, leaf_hash, aunts)?;
+                Some(inner_hash(last_aunt, &right))
+            }
+        }
+    }
+}
+
 // returns the largest power of 2 less than length
 fn get_split_point(length: usize) -> usize {
     match length {
@@ -127,4 +260,134 @@ mod tests {
         let hash = inner_hash(left_string.as_bytes(), right_string.as_bytes());
         assert_eq!(node_hash, &hash);
     }
+
+    /// Vectors of `TestHashFromByteSlices` in Tendermint's `crypto/merkle`
+    #[test]
+    fn test_go_simple_hash_from_byte_slices() {
+        let vectors: Vec<(Vec<Vec<u8>>, &str)> = vec![
+            (
+                vec![vec![1, 2, 3]],
+                "054edec1d0211f624fed0cbca9d4f9400b0e491c43742af2c5b0abebf0c990d8",
+            ),
+            (
+                vec![vec![]],
+                "6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d",
+            ),
+            (
+                vec![vec![1, 2, 3], vec![4, 5, 6]],
+                "82e6cfce00453804379b53962939eaa7906b39904be0813fcadd31b100773c4b",
+            ),
+            (
+                vec![vec![1, 2], vec![3, 4], vec![5, 6], vec![7, 8], vec![9, 10]],
+                "f326493eceab4f2d9ffbc78c59432a0a005d6ea98392045c74df5d14a113be18",
+            ),
+        ];
+
+        for (leaves, root_hex) in vectors {
+            let root = hex::decode(root_hex).unwrap();
+            assert_eq!(
+                &simple_hash_from_byte_vectors(leaves.clone())[..],
+                &root[..]
+            );
+            assert_eq!(&simple_proofs_from_byte_vectors(leaves).0[..], &root[..]);
+        }
+    }
+
+    /// Proofs of the leaves of the last tree of `TestHashFromByteSlices`, which
+    /// must lead to its root as computed by Tendermint
+    #[test]
+    fn test_go_simple_proofs_from_byte_slices() {
+        let leaves = vec![vec![1, 2], vec![3, 4], vec![5, 6], vec![7, 8], vec![9, 10]];
+        let mut root = [0; HASH_SIZE];
+        root.copy_from_slice(
+            &hex::decode("f326493eceab4f2d9ffbc78c59432a0a005d6ea98392045c74df5d14a113be18")
+                .unwrap(),
+        );
+
+        let leaf_hashes = [
+            "ae4b3280e56e2faf83f414a6e3dabe9d5fbe18976544c05fed121accb85b53fc",
+            "ed2139a910c69105ce3628d7aae9c530ad4cbb6aa45a683a0eb02f85a2807287",
+            "87dea70e0fde5a5d7a30aa1a9a062246c27dad6d5b360030b46b76a722bd5ee7",
+            "6ecd1ea1fd08271847fd320ca657408861173c041030f654af163d3f038cec25",
+            "90825dfc453a0701a688854f9b4f195f253574e80678cf3a885db4fa8bfefa42",
+        ];
+        let aunts: [&[&str]; 5] = [
+            &[
+                leaf_hashes[1],
+                "469f9e905948e6a78ed94c3debb990e455b9e67dbc33d07ef16442c0f1d29bfe",
+                leaf_hashes[4],
+            ],
+            &[
+                leaf_hashes[0],
+                "469f9e905948e6a78ed94c3debb990e455b9e67dbc33d07ef16442c0f1d29bfe",
+                leaf_hashes[4],
+            ],
+            &[
+                leaf_hashes[3],
+                "5564155a2da076daa766119fb3863b56b463fde2b5ca5f644ced5fd47a8488da",
+                leaf_hashes[4],
+            ],
+            &[
+                leaf_hashes[2],
+                "5564155a2da076daa766119fb3863b56b463fde2b5ca5f644ced5fd47a8488da",
+                leaf_hashes[4],
+            ],
+            &["8d897f1c3ce8336170f0757eae08206f35b865afc291b3b3a66845d53ab2a732"],
+        ];
+
+        let (_, proofs) = simple_proofs_from_byte_vectors(leaves.clone());
+
+        for (i, proof) in proofs.iter().enumerate() {
+            assert_eq!(proof.leaf_hash, hex::decode(leaf_hashes[i]).unwrap());
+            assert_eq!(
+                proof.aunts,
+                aunts[i]
+                    .iter()
+                    .map(|aunt| hex::decode(aunt).unwrap())
+                    .collect::<Vec<_>>()
+            );
+            proof.verify(&root, &leaves[i]).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_simple_proofs() {
+        for total in 0..=100 {
+            let leaves: Vec<Vec<u8>> = (0..total).map(|i| format!("leaf{}", i).into()).collect();
+
+            let (root, proofs) = simple_proofs_from_byte_vectors(leaves.clone());
+            assert_eq!(root, simple_hash_from_byte_vectors(leaves.clone()));
+            assert_eq!(proofs.len(), total);
+
+            for (i, proof) in proofs.iter().enumerate() {
+                assert_eq!(proof.index, i as u64);
+                assert_eq!(proof.total, total as u64);
+                proof.verify(&root, &leaves[i]).unwrap();
+
+                // Wrong leaf
+                assert!(proof.verify(&root, b"not a leaf").is_err());
+
+                // Wrong root
+                assert!(proof.verify(&[0; HASH_SIZE], &leaves[i]).is_err());
+
+                // Tampered aunts
+                for j in 0..proof.aunts.len() {
+                    let mut tampered = proof.clone();
+                    tampered.aunts[j][0] ^= 1;
+                    assert!(tampered.verify(&root, &leaves[i]).is_err());
+                }
+
+                let mut tampered = proof.clone();
+                tampered.aunts.push(vec![0; HASH_SIZE]);
+                assert!(tampered.verify(&root, &leaves[i]).is_err());
+
+                // Wrong index
+                let mut tampered = proof.clone();
+                tampered.index = (tampered.index + 1) % tampered.total;
+                if total > 1 {
+                    assert!(tampered.verify(&root, &leaves[i]).is_err());
+                }
+            }
+        }
+    }
 }
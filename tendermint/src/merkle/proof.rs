@@ -1,6 +1,23 @@
 //! Merkle proofs
+//!
+//! Proofs returned by ABCI queries consist of a list of proof ops, each of
+//! which computes the root of a Merkle tree from the value of one of its
+//! leaves. Chaining them from the queried value up yields a root which can be
+//! checked against the `app_hash` of a verified header.
+//!
+//! The following proof ops, produced by Cosmos SDK applications, are supported:
+//!
+//! - `iavl:v`: existence of a value in an IAVL tree (ie. in a store)
+//! - `multistore`: root hash of a store within the app hash
 
-use crate::serializers;
+mod iavl;
+mod multistore;
+
+use crate::{
+    error::{Error, Kind},
+    serializers,
+};
+use anomaly::fail;
 use serde::{Deserialize, Serialize};
 
 /// Proof is Merkle proof defined by the list of ProofOps
@@ -11,6 +28,54 @@ pub struct Proof {
     pub ops: Vec<ProofOp>,
 }
 
+impl Proof {
+    /// Verify that `value` is stored under the given path of `keys` in the
+    /// Merkle tree with the given `root`, typically the `app_hash` of a header
+    /// verified by a light client.
+    ///
+    /// The keys go from the outermost tree to the innermost one, ie. for a
+    /// Cosmos SDK application, the name of the store followed by the key of the
+    /// value in that store. The proof ops are run from the leaf value up, each
+    /// one consuming the last remaining key if it has one.
+    pub fn verify_value(&self, root: &[u8], keys: &[&[u8]], value: &[u8]) -> Result<(), Error> {
+        let mut keys = keys.to_vec();
+        let mut hash = value.to_vec();
+
+        for (i, op) in self.ops.iter().enumerate() {
+            if !op.key.is_empty() {
+                match keys.pop() {
+                    Some(key) if key == op.key.as_slice() => (),
+                    Some(key) => fail!(
+                        Kind::InvalidProof,
+                        "key mismatch on proof op #{}: expected {} but got {}",
+                        i,
+                        String::from_utf8_lossy(key),
+                        String::from_utf8_lossy(&op.key)
+                    ),
+                    None => fail!(
+                        Kind::InvalidProof,
+                        "no key left for proof op #{}: got {}",
+                        i,
+                        String::from_utf8_lossy(&op.key)
+                    ),
+                }
+            }
+
+            hash = op.run(&hash)?;
+        }
+
+        if hash != root {
+            fail!(Kind::InvalidProof, "computed root hash does not match");
+        }
+
+        if !keys.is_empty() {
+            fail!(Kind::InvalidProof, "not all keys consumed by the proof");
+        }
+
+        Ok(())
+    }
+}
+
 /// ProofOp defines an operation used for calculating Merkle root
 /// The data could be arbitrary format, providing necessary data
 /// for example neighbouring node hash
@@ -28,14 +93,34 @@ pub struct ProofOp {
     pub data: Vec<u8>,
 }
 
+impl ProofOp {
+    /// Compute the root of the tree this op proves `value` belongs to
+    pub fn run(&self, value: &[u8]) -> Result<Vec<u8>, Error> {
+        match self.field_type.as_str() {
+            iavl::VALUE_OP_TYPE => iavl::ValueOp::parse(&self.data)?.run(&self.key, value),
+            multistore::OP_TYPE => {
+                multistore::MultiStoreProofOp::parse(&self.data)?.run(&self.key, value)
+            }
+            other => fail!(Kind::InvalidProof, "unsupported proof op type: {}", other),
+        }
+    }
+}
+
+// Amino encoding of a byte slice, ie. prefixed with its length
+fn encode_byte_slice(bytes: &[u8], buf: &mut Vec<u8>) {
+    prost_amino::encoding::encode_varint(bytes.len() as u64, buf);
+    buf.extend_from_slice(bytes);
+}
+
 #[cfg(test)]
 mod test {
-    use super::Proof;
+    use super::{iavl, multistore, Proof, ProofOp};
     use crate::test::test_serialization_roundtrip;
+    use prost_amino::Message;
+    use sha2::{Digest, Sha256};
+    use subtle_encoding::hex;
 
-    #[test]
-    fn serialization_roundtrip() {
-        let payload = r#"
+    const PAYLOAD: &str = r#"
         {
             "ops": [
                 {
@@ -50,6 +135,118 @@ mod test {
                 }
             ]
         }"#;
-        test_serialization_roundtrip::<Proof>(payload);
+
+    #[test]
+    fn serialization_roundtrip() {
+        test_serialization_roundtrip::<Proof>(PAYLOAD);
+    }
+
+    #[test]
+    fn iavl_root_is_store_hash() {
+        let proof: Proof = serde_json::from_str(PAYLOAD).unwrap();
+
+        let value_op = iavl::ValueOp::parse(&proof.ops[0].data).unwrap();
+        let store_root = value_op.proof.unwrap().compute_root_hash().unwrap();
+
+        // Root of the "ibc" store, as committed to by the multistore proof
+        let app_hash = proof.ops[1].run(&store_root).unwrap();
+        assert_eq!(
+            app_hash,
+            hex::decode_upper("BF31FB7455460018ED0DD9A2A716E88239F467D9A11BF442C7263E994636151F")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn wrong_value() {
+        let proof: Proof = serde_json::from_str(PAYLOAD).unwrap();
+        let app_hash =
+            hex::decode_upper("BF31FB7455460018ED0DD9A2A716E88239F467D9A11BF442C7263E994636151F")
+                .unwrap();
+
+        assert!(proof
+            .verify_value(
+                &app_hash,
+                &[b"ibc", b"consensusState/ibconeclient/22"],
+                b"not the value"
+            )
+            .is_err());
+    }
+
+    fn single_leaf_proof(store: &str, key: &[u8], value: &[u8]) -> (Proof, Vec<u8>) {
+        let range_proof = iavl::RangeProof {
+            left_path: vec![],
+            inner_nodes: vec![],
+            leaves: vec![iavl::ProofLeafNode {
+                key: key.to_vec(),
+                value_hash: Sha256::digest(value).to_vec(),
+                version: 1,
+            }],
+        };
+        let store_root = range_proof.compute_root_hash().unwrap();
+
+        let multistore_proof = multistore::MultiStoreProof {
+            store_infos: vec![multistore::StoreInfo {
+                name: store.to_owned(),
+                core: Some(multistore::StoreCore {
+                    commit_id: Some(multistore::CommitId {
+                        version: 1,
+                        hash: store_root,
+                    }),
+                }),
+            }],
+        };
+        let app_hash = multistore_proof.compute_root_hash();
+
+        let mut iavl_data = vec![];
+        iavl::ValueOp {
+            proof: Some(range_proof),
+        }
+        .encode_length_delimited(&mut iavl_data)
+        .unwrap();
+
+        let mut multistore_data = vec![];
+        multistore::MultiStoreProofOp {
+            proof: Some(multistore_proof),
+        }
+        .encode(&mut multistore_data)
+        .unwrap();
+
+        let proof = Proof {
+            ops: vec![
+                ProofOp {
+                    field_type: iavl::VALUE_OP_TYPE.to_owned(),
+                    key: key.to_vec(),
+                    data: iavl_data,
+                },
+                ProofOp {
+                    field_type: multistore::OP_TYPE.to_owned(),
+                    key: store.as_bytes().to_vec(),
+                    data: multistore_data,
+                },
+            ],
+        };
+
+        (proof, app_hash)
+    }
+
+    #[test]
+    fn verify_value() {
+        let (proof, app_hash) = single_leaf_proof("bank", b"balance", b"42");
+
+        proof
+            .verify_value(&app_hash, &[b"bank", b"balance"], b"42")
+            .unwrap();
+
+        assert!(proof
+            .verify_value(&app_hash, &[b"bank", b"balance"], b"43")
+            .is_err());
+        assert!(proof
+            .verify_value(&app_hash, &[b"acc", b"balance"], b"42")
+            .is_err());
+        assert!(proof.verify_value(&app_hash, &[b"balance"], b"42").is_err());
+        assert!(proof
+            .verify_value(&[0; 32], &[b"bank", b"balance"], b"42")
+            .is_err());
     }
 }
@@ -0,0 +1,222 @@
+//! IAVL proofs of existence (`iavl:v` proof ops), as produced by the IAVL trees
+//! backing the stores of Cosmos SDK applications.
+//!
+//! <https://github.com/tendermint/iavl/blob/v0.13.3/proof_iavl_value.go>
+
+use super::encode_byte_slice;
+use crate::error::{Error, Kind};
+use anomaly::{fail, format_err};
+use prost_amino::Message;
+use prost_amino_derive::Message;
+use sha2::{Digest, Sha256};
+
+/// Type of the IAVL value proof op
+pub const VALUE_OP_TYPE: &str = "iavl:v";
+
+/// Proof that a value is stored in an IAVL tree, under the key of the proof op
+#[derive(Clone, PartialEq, Message)]
+pub struct ValueOp {
+    #[prost_amino(message, tag = "1")]
+    pub proof: Option<RangeProof>,
+}
+
+impl ValueOp {
+    /// Parse the (length-prefixed, amino encoded) data of an `iavl:v` proof op
+    pub fn parse(data: &[u8]) -> Result<Self, Error> {
+        Self::decode_length_delimited(data).map_err(|e| Kind::Parse.context(e).into())
+    }
+
+    /// Compute the root hash of the tree, after checking that it contains
+    /// `value` under `key`.
+    pub fn run(&self, key: &[u8], value: &[u8]) -> Result<Vec<u8>, Error> {
+        let proof = self
+            .proof
+            .as_ref()
+            .ok_or_else(|| format_err!(Kind::InvalidProof, "missing IAVL range proof"))?;
+
+        let root = proof.compute_root_hash()?;
+        proof.verify_item(key, value)?;
+
+        Ok(root)
+    }
+}
+
+/// Proof of a range of leaves of an IAVL tree
+#[derive(Clone, PartialEq, Message)]
+pub struct RangeProof {
+    /// Path from the root to the leftmost leaf
+    #[prost_amino(message, repeated, tag = "1")]
+    pub left_path: Vec<ProofInnerNode>,
+
+    /// Paths to the other leaves, from their lowest common ancestor with the
+    /// previous ones
+    #[prost_amino(message, repeated, tag = "2")]
+    pub inner_nodes: Vec<PathToLeaf>,
+
+    /// Leaves, sorted by key
+    #[prost_amino(message, repeated, tag = "3")]
+    pub leaves: Vec<ProofLeafNode>,
+}
+
+impl RangeProof {
+    /// Compute the root hash of the tree from the leaves of the proof
+    pub fn compute_root_hash(&self) -> Result<Vec<u8>, Error> {
+        if self.leaves.is_empty() {
+            fail!(Kind::InvalidProof, "no leaves in IAVL range proof");
+        }
+
+        if self.inner_nodes.len() + 1 != self.leaves.len() {
+            fail!(
+                Kind::InvalidProof,
+                "IAVL range proof should have one more leaf than inner paths"
+            );
+        }
+
+        let mut leaves = self.leaves.as_slice();
+        let mut inner_nodes = self.inner_nodes.as_slice();
+        let (root, done) = compute_hash(&self.left_path, &mut leaves, &mut inner_nodes)?;
+
+        if !done {
+            fail!(Kind::InvalidProof, "left over leaves in IAVL range proof");
+        }
+
+        Ok(root)
+    }
+
+    /// Check that the proof contains a leaf for `value` under `key`
+    pub fn verify_item(&self, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        let leaf = self
+            .leaves
+            .binary_search_by(|leaf| leaf.key.as_slice().cmp(key))
+            .map(|i| &self.leaves[i])
+            .map_err(|_| format_err!(Kind::InvalidProof, "leaf key not found in proof"))?;
+
+        if leaf.value_hash.as_slice() != Sha256::digest(value).as_slice() {
+            fail!(Kind::InvalidProof, "leaf value hash mismatch");
+        }
+
+        Ok(())
+    }
+}
+
+/// Compute the hash of the subtree whose leftmost leaf is reached by `path`,
+/// consuming the leaves and inner paths proven along the way.
+///
+/// Returns whether all the leaves have been consumed.
+fn compute_hash<'a>(
+    path: &[ProofInnerNode],
+    leaves: &mut &'a [ProofLeafNode],
+    inner_nodes: &mut &'a [PathToLeaf],
+) -> Result<(Vec<u8>, bool), Error> {
+    let current_leaves: &'a [ProofLeafNode] = *leaves;
+    let (leaf, rest) = current_leaves
+        .split_first()
+        .ok_or_else(|| format_err!(Kind::InvalidProof, "missing leaf in IAVL range proof"))?;
+    *leaves = rest;
+
+    let hash = path
+        .iter()
+        .rev()
+        .fold(leaf.hash(), |hash, node| node.hash(&hash));
+
+    if leaves.is_empty() {
+        return Ok((hash, true));
+    }
+
+    // Prove the remaining leaves along the right children of the path,
+    // starting from the leaf-most ones
+    for node in path.iter().rev().filter(|node| !node.right.is_empty()) {
+        let current_inner_nodes: &'a [PathToLeaf] = *inner_nodes;
+        let (inners, rest) = current_inner_nodes.split_first().ok_or_else(|| {
+            format_err!(Kind::InvalidProof, "missing inner path in IAVL range proof")
+        })?;
+        *inner_nodes = rest;
+
+        let (derived_root, done) = compute_hash(&inners.nodes, leaves, inner_nodes)?;
+
+        if derived_root != node.right {
+            fail!(
+                Kind::InvalidProof,
+                "intermediate root hash mismatch in IAVL range proof"
+            );
+        }
+
+        if done {
+            return Ok((hash, true));
+        }
+    }
+
+    Ok((hash, false))
+}
+
+/// Path of inner nodes leading to a leaf
+#[derive(Clone, PartialEq, Message)]
+pub struct PathToLeaf {
+    #[prost_amino(message, repeated, tag = "1")]
+    pub nodes: Vec<ProofInnerNode>,
+}
+
+/// Inner node of an IAVL tree, with the hash of one of its children
+#[derive(Clone, PartialEq, Message)]
+pub struct ProofInnerNode {
+    #[prost_amino(sint32, tag = "1")]
+    pub height: i32,
+    #[prost_amino(int64, tag = "2")]
+    pub size: i64,
+    #[prost_amino(int64, tag = "3")]
+    pub version: i64,
+    #[prost_amino(bytes, tag = "4")]
+    pub left: Vec<u8>,
+    #[prost_amino(bytes, tag = "5")]
+    pub right: Vec<u8>,
+}
+
+impl ProofInnerNode {
+    /// Hash of the node, given the hash of its child on the path
+    fn hash(&self, child_hash: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![];
+        encode_signed_varint(i64::from(self.height), &mut bytes);
+        encode_signed_varint(self.size, &mut bytes);
+        encode_signed_varint(self.version, &mut bytes);
+
+        if self.left.is_empty() {
+            encode_byte_slice(child_hash, &mut bytes);
+            encode_byte_slice(&self.right, &mut bytes);
+        } else {
+            encode_byte_slice(&self.left, &mut bytes);
+            encode_byte_slice(child_hash, &mut bytes);
+        }
+
+        Sha256::digest(&bytes).to_vec()
+    }
+}
+
+/// Leaf of an IAVL tree
+#[derive(Clone, PartialEq, Message)]
+pub struct ProofLeafNode {
+    #[prost_amino(bytes, tag = "1")]
+    pub key: Vec<u8>,
+    #[prost_amino(bytes, tag = "2")]
+    pub value_hash: Vec<u8>,
+    #[prost_amino(int64, tag = "3")]
+    pub version: i64,
+}
+
+impl ProofLeafNode {
+    /// Hash of the leaf, ie. of a node of height 0 and size 1
+    fn hash(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        encode_signed_varint(0, &mut bytes);
+        encode_signed_varint(1, &mut bytes);
+        encode_signed_varint(self.version, &mut bytes);
+        encode_byte_slice(&self.key, &mut bytes);
+        encode_byte_slice(&self.value_hash, &mut bytes);
+
+        Sha256::digest(&bytes).to_vec()
+    }
+}
+
+// Zigzag varint encoding of signed integers, as used to hash IAVL nodes
+fn encode_signed_varint(value: i64, buf: &mut Vec<u8>) {
+    prost_amino::encoding::encode_varint(((value << 1) ^ (value >> 63)) as u64, buf);
+}
@@ -0,0 +1,129 @@
+//! Multistore proofs (`multistore` proof ops), linking the root hash of one of
+//! the stores of a Cosmos SDK application to the app hash.
+//!
+//! <https://github.com/cosmos/cosmos-sdk/blob/v0.38.4/store/rootmulti/proof.go>
+
+use super::encode_byte_slice;
+use crate::{
+    error::{Error, Kind},
+    merkle::simple_hash_from_byte_vectors,
+};
+use anomaly::{fail, format_err};
+use prost_amino::Message;
+use prost_amino_derive::Message;
+use sha2::{Digest, Sha256};
+
+/// Type of the multistore proof op
+pub const OP_TYPE: &str = "multistore";
+
+/// Proof that a store, named after the key of the proof op, has a given root hash
+#[derive(Clone, PartialEq, Message)]
+pub struct MultiStoreProofOp {
+    #[prost_amino(message, tag = "1")]
+    pub proof: Option<MultiStoreProof>,
+}
+
+impl MultiStoreProofOp {
+    /// Parse the (amino encoded) data of a `multistore` proof op
+    pub fn parse(data: &[u8]) -> Result<Self, Error> {
+        Self::decode(data).map_err(|e| Kind::Parse.context(e).into())
+    }
+
+    /// Compute the app hash, after checking that the store named `key` has
+    /// `value` as its root hash.
+    pub fn run(&self, key: &[u8], value: &[u8]) -> Result<Vec<u8>, Error> {
+        let proof = self
+            .proof
+            .as_ref()
+            .ok_or_else(|| format_err!(Kind::InvalidProof, "missing multistore proof"))?;
+
+        let store_info = proof
+            .store_infos
+            .iter()
+            .find(|store_info| store_info.name.as_bytes() == key)
+            .ok_or_else(|| {
+                format_err!(
+                    Kind::InvalidProof,
+                    "store {} not found in multistore proof",
+                    String::from_utf8_lossy(key)
+                )
+            })?;
+
+        if store_info.commit_hash() != value {
+            fail!(
+                Kind::InvalidProof,
+                "root hash mismatch for store {}",
+                store_info.name
+            );
+        }
+
+        Ok(proof.compute_root_hash())
+    }
+}
+
+/// Commit information of all the stores of an application
+#[derive(Clone, PartialEq, Message)]
+pub struct MultiStoreProof {
+    #[prost_amino(message, repeated, tag = "1")]
+    pub store_infos: Vec<StoreInfo>,
+}
+
+impl MultiStoreProof {
+    /// Compute the app hash, ie. the simple Merkle root of the stores
+    /// sorted by name.
+    pub fn compute_root_hash(&self) -> Vec<u8> {
+        let mut pairs = self
+            .store_infos
+            .iter()
+            .map(|store_info| {
+                // The commit hash is hashed once as the store's hash, and once
+                // more as the value of the key/value pair.
+                let value_hash = Sha256::digest(&Sha256::digest(store_info.commit_hash()));
+
+                let mut pair = vec![];
+                encode_byte_slice(store_info.name.as_bytes(), &mut pair);
+                encode_byte_slice(&value_hash, &mut pair);
+                (store_info.name.as_str(), pair)
+            })
+            .collect::<Vec<_>>();
+
+        pairs.sort();
+
+        simple_hash_from_byte_vectors(pairs.into_iter().map(|(_, pair)| pair).collect()).to_vec()
+    }
+}
+
+/// Commit information of a store
+#[derive(Clone, PartialEq, Message)]
+pub struct StoreInfo {
+    #[prost_amino(string, tag = "1")]
+    pub name: String,
+    #[prost_amino(message, tag = "2")]
+    pub core: Option<StoreCore>,
+}
+
+impl StoreInfo {
+    /// Root hash of the store
+    pub fn commit_hash(&self) -> &[u8] {
+        self.core
+            .as_ref()
+            .and_then(|core| core.commit_id.as_ref())
+            .map_or(&[][..], |commit_id| commit_id.hash.as_slice())
+    }
+}
+
+/// Commit of a store
+#[derive(Clone, PartialEq, Message)]
+pub struct StoreCore {
+    #[prost_amino(message, tag = "1")]
+    pub commit_id: Option<CommitId>,
+}
+
+/// Version and root hash of a store
+#[derive(Clone, PartialEq, Message)]
+pub struct CommitId {
+    #[prost_amino(int64, tag = "1")]
+    pub version: i64,
+    #[prost_amino(bytes, tag = "2")]
+    pub hash: Vec<u8>,
+}
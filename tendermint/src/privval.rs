@@ -0,0 +1,25 @@
+//! Remote signer ("privval") protocol
+//!
+//! Tendermint validators can delegate the signing of votes and proposals to a
+//! remote signer: the validator node listens on `priv_validator_laddr`, and the
+//! signer dials it and answers its requests. Requests and responses are
+//! length-prefixed amino messages, exchanged over a Unix domain socket or over
+//! a TCP connection authenticated and encrypted with `SecretConnection`.
+//!
+//! - `server`: the remote signer, which signs requests with a pluggable signer,
+//!   refusing to sign conflicting messages thanks to its `LastSignState`
+//! - `client`: the validator side, mostly useful to drive a signer in tests
+
+pub mod client;
+mod connection;
+mod last_sign_state;
+mod message;
+pub mod server;
+
+pub use self::{
+    client::Client,
+    connection::{Connection, Listener},
+    last_sign_state::LastSignState,
+    message::{Request, Response, MAX_MSG_LEN},
+    server::Server,
+};
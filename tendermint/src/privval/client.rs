@@ -0,0 +1,89 @@
+//! Validator side of the privval protocol, which sends requests to a remote
+//! signer. Mostly useful to drive a signer in tests.
+
+use super::{
+    connection::{Connection, Listener},
+    message::{Request, Response},
+};
+use crate::{
+    amino_types::{
+        PingRequest, PubKeyRequest, SignProposalRequest, SignVoteRequest, SignedProposalResponse,
+        SignedVoteResponse,
+    },
+    error::{Error, Kind},
+    public_key::PublicKey,
+};
+use anomaly::{fail, format_err};
+use signatory::ed25519;
+
+/// Client sending requests to a remote signer
+pub struct Client {
+    connection: Box<dyn Connection>,
+}
+
+impl Client {
+    /// Create a new client sending its requests over the given connection
+    pub fn new(connection: Box<dyn Connection>) -> Self {
+        Self { connection }
+    }
+
+    /// Accept a connection from a remote signer on the given listener.
+    ///
+    /// TCP connections are authenticated with the given identity key.
+    pub fn accept(listener: &Listener, identity_key: &ed25519::Seed) -> Result<Self, Error> {
+        Ok(Self::new(listener.accept(identity_key)?))
+    }
+
+    /// Get the public key of the remote signer
+    pub fn public_key(&mut self) -> Result<PublicKey, Error> {
+        match self.request(Request::ShowPublicKey(PubKeyRequest {}))? {
+            Response::PublicKey(response) => {
+                if response.pub_key_ed25519.len() != ed25519::PUBLIC_KEY_SIZE {
+                    fail!(Kind::InvalidKey, "invalid public key from remote signer");
+                }
+
+                Ok(PublicKey::from(response))
+            }
+            other => unexpected_response(other),
+        }
+    }
+
+    /// Request the remote signer to sign the given vote
+    pub fn sign_vote(&mut self, request: SignVoteRequest) -> Result<SignedVoteResponse, Error> {
+        match self.request(Request::SignVote(request))? {
+            Response::SignedVote(response) => Ok(response),
+            other => unexpected_response(other),
+        }
+    }
+
+    /// Request the remote signer to sign the given proposal
+    pub fn sign_proposal(
+        &mut self,
+        request: SignProposalRequest,
+    ) -> Result<SignedProposalResponse, Error> {
+        match self.request(Request::SignProposal(request))? {
+            Response::SignedProposal(response) => Ok(response),
+            other => unexpected_response(other),
+        }
+    }
+
+    /// Check that the remote signer is alive
+    pub fn ping(&mut self) -> Result<(), Error> {
+        match self.request(Request::ReplyPing(PingRequest {}))? {
+            Response::Ping(_) => Ok(()),
+            other => unexpected_response(other),
+        }
+    }
+
+    /// Send the given request and wait for the response
+    fn request(&mut self, request: Request) -> Result<Response, Error> {
+        request.write(&mut self.connection)?;
+
+        Response::read(&mut self.connection)?
+            .ok_or_else(|| format_err!(Kind::Io, "connection closed by the remote signer").into())
+    }
+}
+
+fn unexpected_response<T>(response: Response) -> Result<T, Error> {
+    fail!(Kind::Protocol, "unexpected response: {:?}", response)
+}
@@ -0,0 +1,142 @@
+//! Transports of the privval protocol: TCP connections, which are authenticated
+//! and encrypted with `SecretConnection`, and plain Unix domain sockets.
+
+use crate::{
+    error::{Error, Kind},
+    net, node,
+    secret_connection::SecretConnection,
+};
+use anomaly::fail;
+use signatory::{ed25519, public_key::PublicKeyed};
+use signatory_dalek::Ed25519Signer;
+use std::{
+    io::{Read, Write},
+    net::{TcpListener, TcpStream},
+    os::unix::net::{UnixListener, UnixStream},
+};
+
+/// Connection between a validator and a remote signer
+pub trait Connection: Read + Write + Send + Sync {}
+
+impl<T: Read + Write + Send + Sync> Connection for T {}
+
+/// Dial the given address.
+///
+/// TCP connections are authenticated with the given identity key. If the address
+/// contains a peer ID, the remote peer must authenticate with the corresponding key.
+pub(super) fn dial(
+    addr: &net::Address,
+    identity_key: &ed25519::Seed,
+) -> Result<Box<dyn Connection>, Error> {
+    match addr {
+        net::Address::Tcp {
+            peer_id,
+            host,
+            port,
+        } => {
+            let socket =
+                TcpStream::connect((host.as_str(), *port)).map_err(|e| Kind::Io.context(e))?;
+
+            secret_connection(socket, identity_key, *peer_id)
+        }
+        net::Address::Unix { path } => {
+            let socket = UnixStream::connect(path).map_err(|e| Kind::Io.context(e))?;
+            Ok(Box::new(socket))
+        }
+    }
+}
+
+/// Listener for connections from a remote signer
+pub enum Listener {
+    /// TCP listener
+    Tcp(TcpListener),
+
+    /// Unix domain socket listener
+    Unix(UnixListener),
+}
+
+impl Listener {
+    /// Bind a new listener to the given address
+    pub fn bind(addr: &net::Address) -> Result<Self, Error> {
+        let listener = match addr {
+            net::Address::Tcp { host, port, .. } => Listener::Tcp(
+                TcpListener::bind((host.as_str(), *port)).map_err(|e| Kind::Io.context(e))?,
+            ),
+            net::Address::Unix { path } => {
+                Listener::Unix(UnixListener::bind(path).map_err(|e| Kind::Io.context(e))?)
+            }
+        };
+
+        Ok(listener)
+    }
+
+    /// Get the address this listener is bound to
+    pub fn local_address(&self) -> Result<net::Address, Error> {
+        match self {
+            Listener::Tcp(listener) => {
+                let addr = listener.local_addr().map_err(|e| Kind::Io.context(e))?;
+
+                Ok(net::Address::Tcp {
+                    peer_id: None,
+                    host: addr.ip().to_string(),
+                    port: addr.port(),
+                })
+            }
+            Listener::Unix(listener) => {
+                let addr = listener.local_addr().map_err(|e| Kind::Io.context(e))?;
+
+                match addr.as_pathname() {
+                    Some(path) => Ok(net::Address::Unix {
+                        path: path.to_owned(),
+                    }),
+                    None => fail!(Kind::Io, "unnamed Unix domain socket"),
+                }
+            }
+        }
+    }
+
+    /// Accept a connection from a remote signer.
+    ///
+    /// TCP connections are authenticated with the given identity key.
+    pub fn accept(&self, identity_key: &ed25519::Seed) -> Result<Box<dyn Connection>, Error> {
+        match self {
+            Listener::Tcp(listener) => {
+                let (socket, _) = listener.accept().map_err(|e| Kind::Io.context(e))?;
+                secret_connection(socket, identity_key, None)
+            }
+            Listener::Unix(listener) => {
+                let (socket, _) = listener.accept().map_err(|e| Kind::Io.context(e))?;
+                Ok(Box::new(socket))
+            }
+        }
+    }
+}
+
+/// Perform the `SecretConnection` handshake over the given socket, and check that
+/// the remote peer authenticated with the expected peer ID, if any.
+fn secret_connection(
+    socket: TcpStream,
+    identity_key: &ed25519::Seed,
+    peer_id: Option<node::Id>,
+) -> Result<Box<dyn Connection>, Error> {
+    let signer = Ed25519Signer::from(identity_key);
+    let public_key = signer.public_key().map_err(|e| Kind::Crypto.context(e))?;
+
+    let connection = SecretConnection::new(socket, &public_key, &signer)?;
+
+    if let Some(expected_peer_id) = peer_id {
+        // The remote public key of a `SecretConnection` is always an Ed25519 key
+        let remote_peer_id = node::Id::from(connection.remote_pubkey().ed25519().unwrap());
+
+        if remote_peer_id != expected_peer_id {
+            fail!(
+                Kind::Crypto,
+                "expected peer ID {}, got {}",
+                expected_peer_id,
+                remote_peer_id
+            );
+        }
+    }
+
+    Ok(Box::new(connection))
+}
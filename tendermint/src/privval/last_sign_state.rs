@@ -0,0 +1,450 @@
+//! Last sign state of a validator, persisted in `priv_validator_state.json`.
+//!
+//! Before signing a vote or a proposal, its consensus state (height, round and
+//! step) is checked against the last signed one, so that a validator never
+//! signs conflicting messages (double signing), even across restarts.
+//!
+//! The state file has the same format as the one of Tendermint's `FilePV`:
+//!
+//! <https://github.com/tendermint/tendermint/blob/v0.33.5/privval/file.go>
+
+use crate::{
+    amino_types::{RemoteError, SignableMsg},
+    block, chain, consensus,
+    error::{Error, Kind},
+    serializers,
+};
+use anomaly::format_err;
+use serde::{Deserialize, Serialize};
+use signatory::{ed25519, signature::Signature};
+use std::{
+    cmp::Ordering,
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// Height, round and step of the last vote or proposal signed by a validator,
+/// along with its sign bytes and signature
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LastSignState {
+    /// Last signed height
+    height: block::Height,
+
+    /// Last signed round
+    #[serde(with = "serializers::from_str")]
+    round: i64,
+
+    /// Last signed step: 1 for proposals, 2 for prevotes and 3 for precommits
+    step: i8,
+
+    /// Signature of the last signed message
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        with = "serializers::bytes::base64string"
+    )]
+    signature: Vec<u8>,
+
+    /// Sign bytes of the last signed message
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        with = "serializers::bytes::hexstring"
+    )]
+    signbytes: Vec<u8>,
+
+    /// Path to the state file
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl LastSignState {
+    /// Load the last sign state from the given `priv_validator_state.json` file,
+    /// creating it with an initial state if it does not exist yet.
+    ///
+    /// The location of this file is configured by the `priv_validator_state_file`
+    /// field of `TendermintConfig`, relative to the Tendermint home directory.
+    pub fn load_json_file<P>(path: &P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+
+        if !path.exists() {
+            let state = Self {
+                path: path.to_owned(),
+                ..Self::default()
+            };
+
+            state.save()?;
+            return Ok(state);
+        }
+
+        let json_string = fs::read_to_string(path)
+            .map_err(|e| format_err!(Kind::Parse, "couldn't open {}: {}", path.display(), e))?;
+
+        let state: Self = serde_json::from_str(&json_string)?;
+
+        Ok(Self {
+            path: path.to_owned(),
+            ..state
+        })
+    }
+
+    /// Get the last signed height
+    pub fn height(&self) -> block::Height {
+        self.height
+    }
+
+    /// Get the last signed round
+    pub fn round(&self) -> i64 {
+        self.round
+    }
+
+    /// Get the last signed step
+    pub fn step(&self) -> i8 {
+        self.step
+    }
+
+    /// Check that the given message can be signed for the given chain, ie. that
+    /// it neither regresses nor conflicts with the last signed one.
+    ///
+    /// A message with the same height, round and step as the last signed one can
+    /// only be signed again if it has the same sign bytes, in which case the last
+    /// signature is returned and must be reused instead of signing it again.
+    ///
+    /// As Tendermint signs a message again with a new timestamp when it restarts
+    /// in the middle of a round, a message which only differs from the last
+    /// signed one by its timestamp gets the last signature too: its timestamp is
+    /// then set back to the last signed one, as Tendermint's `FilePV` does.
+    pub fn check<T: SignableMsg + Clone>(
+        &self,
+        chain_id: chain::Id,
+        msg: &mut T,
+    ) -> Result<Option<ed25519::Signature>, RemoteError> {
+        let new_state = msg
+            .consensus_state()
+            .ok_or_else(|| RemoteError::signer_error("invalid consensus state"))?;
+
+        let double_sign = || RemoteError::double_sign(new_state.height.into());
+
+        match self.cmp_state(&new_state) {
+            Ordering::Less => Err(double_sign()),
+            Ordering::Greater => Ok(None),
+            Ordering::Equal => {
+                if self.signbytes.is_empty() {
+                    return Err(double_sign());
+                }
+
+                let last_timestamp =
+                    T::parse_sign_bytes_timestamp(&self.signbytes).map_err(|_| double_sign())?;
+
+                let mut last_msg = msg.clone();
+                if let Some(timestamp) = last_timestamp {
+                    last_msg.set_timestamp(timestamp);
+                }
+
+                if sign_bytes(&last_msg, chain_id)? != self.signbytes {
+                    return Err(double_sign());
+                }
+
+                let signature = ed25519::Signature::from_bytes(&self.signature).map_err(|e| {
+                    RemoteError::signer_error(format!("invalid last signature: {}", e))
+                })?;
+
+                *msg = last_msg;
+                Ok(Some(signature))
+            }
+        }
+    }
+
+    /// Persist the given consensus state and sign bytes as the new last sign
+    /// state, along with their signature.
+    ///
+    /// This must be called once a message, which `check` allowed to sign, has
+    /// been signed, but before its signature is returned.
+    pub fn update(
+        &mut self,
+        new_state: &consensus::State,
+        sign_bytes: Vec<u8>,
+        signature: &ed25519::Signature,
+    ) -> Result<(), RemoteError> {
+        if self.cmp_state(new_state) != Ordering::Greater {
+            return Err(RemoteError::double_sign(new_state.height.into()));
+        }
+
+        let next_state = Self {
+            height: new_state.height,
+            round: new_state.round,
+            step: new_state.step,
+            signature: signature.as_ref().to_vec(),
+            signbytes: sign_bytes,
+            path: self.path.clone(),
+        };
+
+        let last_state = std::mem::replace(self, next_state);
+
+        // Do not return any signature if the new state could not be persisted
+        if let Err(e) = self.save() {
+            *self = last_state;
+            return Err(RemoteError::signer_error(format!(
+                "couldn't persist last sign state: {}",
+                e
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Compare the given consensus state with the last signed one
+    fn cmp_state(&self, new_state: &consensus::State) -> Ordering {
+        (new_state.height, new_state.round, new_state.step).cmp(&(
+            self.height,
+            self.round,
+            self.step,
+        ))
+    }
+
+    /// Atomically save the state to disk, by writing it to a temporary file
+    /// which is then renamed over the state file.
+    fn save(&self) -> Result<(), Error> {
+        let json = serde_json::to_string_pretty(self)?;
+        let tmp_path = self.path.with_extension("json.tmp");
+
+        let mut file = File::create(&tmp_path).map_err(|e| Kind::Io.context(e))?;
+        file.write_all(json.as_bytes())
+            .map_err(|e| Kind::Io.context(e))?;
+        file.sync_all().map_err(|e| Kind::Io.context(e))?;
+
+        fs::rename(&tmp_path, &self.path).map_err(|e| Kind::Io.context(e))?;
+
+        Ok(())
+    }
+}
+
+/// Compute the sign bytes of the given message for the given chain
+pub(crate) fn sign_bytes<T: SignableMsg>(
+    msg: &T,
+    chain_id: chain::Id,
+) -> Result<Vec<u8>, RemoteError> {
+    let mut sign_bytes = vec![];
+    msg.sign_bytes(chain_id, &mut sign_bytes)
+        .map_err(|e| RemoteError::signer_error(e.to_string()))?;
+
+    Ok(sign_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amino_types::{
+        proposal::Proposal, remote_error::RemoteErrorCode, vote::Vote, BlockId,
+        SignProposalRequest, SignVoteRequest, SignedMsgType, TimeMsg,
+    };
+    use std::{env, process};
+
+    fn state_file(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!(
+            "tendermint-last-sign-state-{}-{}.json",
+            name,
+            process::id()
+        ));
+
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn chain_id() -> chain::Id {
+        "test-chain".parse().unwrap()
+    }
+
+    fn vote(height: i64, round: i64, msg_type: SignedMsgType, seconds: i64) -> SignVoteRequest {
+        SignVoteRequest {
+            vote: Some(Vote {
+                vote_type: msg_type.to_u32(),
+                height,
+                round,
+                timestamp: Some(TimeMsg { seconds, nanos: 0 }),
+                validator_address: vec![0xa3; 20],
+                ..Vote::default()
+            }),
+        }
+    }
+
+    fn proposal(height: i64, round: i64) -> SignProposalRequest {
+        SignProposalRequest {
+            proposal: Some(Proposal {
+                msg_type: SignedMsgType::Proposal.to_u32(),
+                height,
+                round,
+                pol_round: -1,
+                ..Proposal::default()
+            }),
+        }
+    }
+
+    fn signature(byte: u8) -> ed25519::Signature {
+        ed25519::Signature::new([byte; 64])
+    }
+
+    /// Record the given message as signed with the given signature
+    fn sign<T: SignableMsg>(last_sign_state: &mut LastSignState, msg: &T, byte: u8) {
+        last_sign_state
+            .update(
+                &msg.consensus_state().unwrap(),
+                sign_bytes(msg, chain_id()).unwrap(),
+                &signature(byte),
+            )
+            .unwrap();
+    }
+
+    fn check<T: SignableMsg + Clone>(
+        last_sign_state: &LastSignState,
+        mut msg: T,
+    ) -> Result<Option<ed25519::Signature>, RemoteError> {
+        last_sign_state.check(chain_id(), &mut msg)
+    }
+
+    fn assert_double_sign<T: std::fmt::Debug>(result: Result<T, RemoteError>) {
+        assert_eq!(
+            result.unwrap_err().code,
+            RemoteErrorCode::DoubleSignError as i32
+        );
+    }
+
+    #[test]
+    fn rejects_regressions_and_conflicts() {
+        let path = state_file("conflicts");
+        let mut last_sign_state = LastSignState::load_json_file(&path).unwrap();
+
+        let prevote = vote(10, 1, SignedMsgType::PreVote, 100);
+        sign(&mut last_sign_state, &prevote, 1);
+
+        // Signing the same message again reuses its signature
+        assert_eq!(
+            check(&last_sign_state, prevote.clone()).unwrap(),
+            Some(signature(1))
+        );
+
+        assert_double_sign(check(
+            &last_sign_state,
+            vote(9, 2, SignedMsgType::PreCommit, 100),
+        ));
+        assert_double_sign(check(
+            &last_sign_state,
+            vote(10, 0, SignedMsgType::PreCommit, 100),
+        ));
+        assert_double_sign(check(&last_sign_state, proposal(10, 1)));
+
+        let mut other_prevote = prevote.clone();
+        other_prevote.vote.as_mut().unwrap().block_id = Some(BlockId::new(vec![0xab; 32], None));
+        assert_double_sign(check(&last_sign_state, other_prevote));
+
+        // Moving forward is fine
+        assert_eq!(
+            check(&last_sign_state, vote(10, 1, SignedMsgType::PreCommit, 100)).unwrap(),
+            None
+        );
+        sign(&mut last_sign_state, &proposal(10, 2), 2);
+        sign(&mut last_sign_state, &proposal(11, 0), 3);
+
+        // Recording a state which doesn't move forward is refused
+        assert_double_sign(last_sign_state.update(
+            &prevote.consensus_state().unwrap(),
+            vec![],
+            &signature(4),
+        ));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn reuses_signature_and_timestamp_of_last_message() {
+        let path = state_file("timestamp");
+        let mut last_sign_state = LastSignState::load_json_file(&path).unwrap();
+
+        sign(
+            &mut last_sign_state,
+            &vote(10, 1, SignedMsgType::PreCommit, 100),
+            1,
+        );
+
+        // After a restart, the same precommit is signed again with a new timestamp
+        let mut precommit = vote(10, 1, SignedMsgType::PreCommit, 200);
+        assert_eq!(
+            last_sign_state.check(chain_id(), &mut precommit).unwrap(),
+            Some(signature(1))
+        );
+        assert_eq!(
+            precommit.vote.unwrap().timestamp,
+            Some(TimeMsg {
+                seconds: 100,
+                nanos: 0
+            })
+        );
+
+        // Any other difference is still double signing
+        let mut precommit = vote(10, 1, SignedMsgType::PreCommit, 200);
+        precommit.vote.as_mut().unwrap().block_id = Some(BlockId::new(vec![0xab; 32], None));
+        assert_double_sign(check(&last_sign_state, precommit));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn persists_state() {
+        let path = state_file("persists");
+
+        let mut last_sign_state = LastSignState::load_json_file(&path).unwrap();
+        assert_eq!(last_sign_state.height().value(), 0);
+
+        let precommit = vote(42, 1, SignedMsgType::PreCommit, 100);
+        sign(&mut last_sign_state, &precommit, 1);
+
+        let reloaded = LastSignState::load_json_file(&path).unwrap();
+        assert_eq!(
+            (reloaded.height().value(), reloaded.round(), reloaded.step()),
+            (42, 1, 3)
+        );
+        assert_eq!(check(&reloaded, precommit).unwrap(), Some(signature(1)));
+        assert_double_sign(check(&reloaded, vote(41, 0, SignedMsgType::PreCommit, 100)));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn tendermint_state_file() {
+        let path = state_file("tendermint");
+
+        // Initial state file written by `tendermint init`
+        fs::write(&path, r#"{"height": "0", "round": "0", "step": 0}"#).unwrap();
+        let last_sign_state = LastSignState::load_json_file(&path).unwrap();
+        assert_eq!(last_sign_state.height().value(), 0);
+        assert_eq!(check(&last_sign_state, proposal(1, 0)).unwrap(), None);
+
+        let precommit = vote(7, 1, SignedMsgType::PreCommit, 100);
+        let json = format!(
+            r#"{{"height": "7", "round": "1", "step": 3, "signature": "{}", "signbytes": "{}"}}"#,
+            String::from_utf8(subtle_encoding::base64::encode(&[5; 64][..])).unwrap(),
+            String::from_utf8(subtle_encoding::hex::encode_upper(
+                sign_bytes(&precommit, chain_id()).unwrap()
+            ))
+            .unwrap()
+        );
+        fs::write(&path, json).unwrap();
+
+        let last_sign_state = LastSignState::load_json_file(&path).unwrap();
+        assert_eq!(
+            check(&last_sign_state, precommit).unwrap(),
+            Some(signature(5))
+        );
+
+        let json: serde_json::Value = serde_json::to_value(&last_sign_state).unwrap();
+        assert_eq!(json["height"], "7");
+        assert_eq!(json["round"], "1");
+        assert_eq!(json["step"], 3);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,234 @@
+//! Requests and responses of the privval protocol, exchanged as
+//! length-prefixed amino messages.
+
+use crate::{
+    amino_types::{
+        compute_prefix, message::AminoMessage, PingRequest, PingResponse, PubKeyRequest,
+        PubKeyResponse, SignProposalRequest, SignVoteRequest, SignedProposalResponse,
+        SignedVoteResponse, PING_PREFIX, PROPOSAL_PREFIX, PUBKEY_PREFIX, VOTE_PREFIX,
+    },
+    error::{Error, Kind},
+};
+use anomaly::fail;
+use once_cell::sync::Lazy;
+use std::io::{Read, Write};
+
+/// Maximum size of an encoded message, excluding its length prefix
+/// (`maxRemoteSignerMsgSize` in Tendermint)
+pub const MAX_MSG_LEN: usize = 1024 * 10;
+
+/// Maximum size of a varint-encoded `u64`
+const MAX_VARINT_LEN: usize = 10;
+
+static SIGNED_VOTE_PREFIX: Lazy<Vec<u8>> =
+    Lazy::new(|| compute_prefix("tendermint/remotesigner/SignedVoteResponse"));
+static SIGNED_PROPOSAL_PREFIX: Lazy<Vec<u8>> =
+    Lazy::new(|| compute_prefix("tendermint/remotesigner/SignedProposalResponse"));
+static PUBKEY_RESPONSE_PREFIX: Lazy<Vec<u8>> =
+    Lazy::new(|| compute_prefix("tendermint/remotesigner/PubKeyResponse"));
+static PING_RESPONSE_PREFIX: Lazy<Vec<u8>> =
+    Lazy::new(|| compute_prefix("tendermint/remotesigner/PingResponse"));
+
+/// Requests sent by a validator to a remote signer
+#[derive(Clone, Debug, PartialEq)]
+pub enum Request {
+    /// Sign the given proposal
+    SignProposal(SignProposalRequest),
+
+    /// Sign the given vote
+    SignVote(SignVoteRequest),
+
+    /// Get the public key of the signer
+    ShowPublicKey(PubKeyRequest),
+
+    /// Check that the signer is alive
+    ReplyPing(PingRequest),
+}
+
+impl Request {
+    /// Read a request from the given stream, or `None` if the stream was closed.
+    pub fn read<R: Read>(r: &mut R) -> Result<Option<Self>, Error> {
+        let msg = match read_msg(r)? {
+            Some(msg) => msg,
+            None => return Ok(None),
+        };
+
+        let request = if msg.starts_with(&*VOTE_PREFIX) {
+            Request::SignVote(decode(&msg)?)
+        } else if msg.starts_with(&*PROPOSAL_PREFIX) {
+            Request::SignProposal(decode(&msg)?)
+        } else if msg.starts_with(&*PUBKEY_PREFIX) {
+            Request::ShowPublicKey(decode(&msg)?)
+        } else if msg.starts_with(&*PING_PREFIX) {
+            Request::ReplyPing(decode(&msg)?)
+        } else {
+            fail!(Kind::Protocol, "unknown request type");
+        };
+
+        Ok(Some(request))
+    }
+
+    /// Write this request to the given stream.
+    pub fn write<W: Write>(&self, w: &mut W) -> Result<(), Error> {
+        let msg = match self {
+            Request::SignProposal(request) => request.bytes_vec_length_delimited(),
+            Request::SignVote(request) => request.bytes_vec_length_delimited(),
+            Request::ShowPublicKey(request) => request.bytes_vec_length_delimited(),
+            Request::ReplyPing(request) => request.bytes_vec_length_delimited(),
+        };
+
+        write_msg(w, &msg)
+    }
+}
+
+/// Responses sent by a remote signer to a validator
+#[derive(Clone, Debug, PartialEq)]
+pub enum Response {
+    /// Signed proposal, or error
+    SignedProposal(SignedProposalResponse),
+
+    /// Signed vote, or error
+    SignedVote(SignedVoteResponse),
+
+    /// Public key of the signer
+    PublicKey(PubKeyResponse),
+
+    /// Reply to a ping
+    Ping(PingResponse),
+}
+
+impl Response {
+    /// Read a response from the given stream, or `None` if the stream was closed.
+    pub fn read<R: Read>(r: &mut R) -> Result<Option<Self>, Error> {
+        let msg = match read_msg(r)? {
+            Some(msg) => msg,
+            None => return Ok(None),
+        };
+
+        let response = if msg.starts_with(&*SIGNED_VOTE_PREFIX) {
+            Response::SignedVote(decode(&msg)?)
+        } else if msg.starts_with(&*SIGNED_PROPOSAL_PREFIX) {
+            Response::SignedProposal(decode(&msg)?)
+        } else if msg.starts_with(&*PUBKEY_RESPONSE_PREFIX) {
+            Response::PublicKey(decode(&msg)?)
+        } else if msg.starts_with(&*PING_RESPONSE_PREFIX) {
+            Response::Ping(decode(&msg)?)
+        } else {
+            fail!(Kind::Protocol, "unknown response type");
+        };
+
+        Ok(Some(response))
+    }
+
+    /// Write this response to the given stream.
+    pub fn write<W: Write>(&self, w: &mut W) -> Result<(), Error> {
+        let msg = match self {
+            Response::SignedProposal(response) => response.bytes_vec_length_delimited(),
+            Response::SignedVote(response) => response.bytes_vec_length_delimited(),
+            Response::PublicKey(response) => response.bytes_vec_length_delimited(),
+            Response::Ping(response) => response.bytes_vec_length_delimited(),
+        };
+
+        write_msg(w, &msg)
+    }
+}
+
+/// Decode an amino message, including its amino prefix
+fn decode<M: prost_amino::Message + Default>(msg: &[u8]) -> Result<M, Error> {
+    M::decode(msg).map_err(|e| Kind::Parse.context(e).into())
+}
+
+/// Read a length-prefixed message, or `None` if the stream was closed
+/// before the message started.
+fn read_msg<R: Read>(r: &mut R) -> Result<Option<Vec<u8>>, Error> {
+    let mut len: u64 = 0;
+
+    for i in 0..MAX_VARINT_LEN {
+        let mut byte = [0u8; 1];
+
+        if r.read(&mut byte).map_err(|e| Kind::Io.context(e))? == 0 {
+            if i == 0 {
+                return Ok(None);
+            }
+
+            fail!(Kind::Io, "stream closed while reading length prefix");
+        }
+
+        len |= u64::from(byte[0] & 0x7f) << (7 * i);
+
+        if byte[0] & 0x80 == 0 {
+            if len > MAX_MSG_LEN as u64 {
+                fail!(Kind::Length, "message of {} bytes is too large", len);
+            }
+
+            let mut msg = vec![0u8; len as usize];
+            r.read_exact(&mut msg).map_err(|e| Kind::Io.context(e))?;
+
+            return Ok(Some(msg));
+        }
+    }
+
+    fail!(Kind::Parse, "invalid length prefix")
+}
+
+/// Write an already length-prefixed message
+fn write_msg<W: Write>(w: &mut W, msg: &[u8]) -> Result<(), Error> {
+    w.write_all(msg).map_err(|e| Kind::Io.context(e))?;
+    w.flush().map_err(|e| Kind::Io.context(e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amino_types::{vote::Vote, SignedMsgType};
+    use std::io::Cursor;
+
+    #[test]
+    fn request_roundtrip() {
+        let requests = vec![
+            Request::SignVote(SignVoteRequest {
+                vote: Some(Vote {
+                    vote_type: SignedMsgType::PreCommit.to_u32(),
+                    height: 42,
+                    ..Vote::default()
+                }),
+            }),
+            Request::ShowPublicKey(PubKeyRequest {}),
+            Request::ReplyPing(PingRequest {}),
+        ];
+
+        let mut buf = vec![];
+        for request in &requests {
+            request.write(&mut buf).unwrap();
+        }
+
+        let mut stream = Cursor::new(buf);
+        for request in requests {
+            assert_eq!(Request::read(&mut stream).unwrap(), Some(request));
+        }
+
+        // The stream is closed
+        assert_eq!(Request::read(&mut stream).unwrap(), None);
+    }
+
+    #[test]
+    fn response_roundtrip() {
+        let response = Response::PublicKey(PubKeyResponse {
+            pub_key_ed25519: vec![0x42; 32],
+        });
+
+        let mut buf = vec![];
+        response.write(&mut buf).unwrap();
+
+        let parsed = Response::read(&mut Cursor::new(buf)).unwrap();
+        assert_eq!(parsed, Some(response));
+    }
+
+    #[test]
+    fn message_too_large() {
+        // varint-encoded length of MAX_MSG_LEN + 1
+        let buf = vec![0x81, 0x50];
+        assert!(Request::read(&mut Cursor::new(buf)).is_err());
+    }
+}
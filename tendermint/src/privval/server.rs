@@ -0,0 +1,150 @@
+//! Remote signer: answers the sign requests of a validator node.
+
+use super::{
+    connection,
+    last_sign_state::{self, LastSignState},
+    message::{Request, Response},
+};
+use crate::{
+    amino_types::{
+        PingResponse, PubKeyResponse, RemoteError, SignableMsg, SignedProposalResponse,
+        SignedVoteResponse,
+    },
+    chain,
+    config::TendermintConfig,
+    error::{Error, Kind},
+    net,
+    public_key::PublicKey,
+};
+use anomaly::format_err;
+use signatory::{ed25519, signature::Signer};
+use std::io::{Read, Write};
+
+/// Remote signer, which signs the votes and proposals requested by a
+/// validator node with a pluggable signer.
+pub struct Server {
+    chain_id: chain::Id,
+    public_key: ed25519::PublicKey,
+    signer: Box<dyn Signer<ed25519::Signature> + Send + Sync>,
+    last_sign_state: LastSignState,
+}
+
+impl Server {
+    /// Create a new server signing the requests for the given chain with the
+    /// given signer, whose public key is `public_key`.
+    ///
+    /// Requests which would regress or conflict with the given last sign state
+    /// are refused with a double sign error.
+    pub fn new(
+        chain_id: chain::Id,
+        public_key: ed25519::PublicKey,
+        signer: impl Signer<ed25519::Signature> + Send + Sync + 'static,
+        last_sign_state: LastSignState,
+    ) -> Self {
+        Self {
+            chain_id,
+            public_key,
+            signer: Box::new(signer),
+            last_sign_state,
+        }
+    }
+
+    /// Dial the validator at the `priv_validator_laddr` of the given config,
+    /// and serve its requests until it closes the connection.
+    ///
+    /// TCP connections are authenticated with the given identity key.
+    pub fn dial_config(
+        &mut self,
+        config: &TendermintConfig,
+        identity_key: &ed25519::Seed,
+    ) -> Result<(), Error> {
+        let addr = config
+            .priv_validator_laddr
+            .as_ref()
+            .ok_or_else(|| format_err!(Kind::Parse, "no priv_validator_laddr configured"))?;
+
+        self.dial(addr, identity_key)
+    }
+
+    /// Dial the validator at the given address, and serve its requests
+    /// until it closes the connection.
+    ///
+    /// TCP connections are authenticated with the given identity key.
+    pub fn dial(&mut self, addr: &net::Address, identity_key: &ed25519::Seed) -> Result<(), Error> {
+        let mut connection = connection::dial(addr, identity_key)?;
+        self.serve(&mut connection)
+    }
+
+    /// Serve the requests read from the given connection until it is closed.
+    pub fn serve<C: Read + Write>(&mut self, connection: &mut C) -> Result<(), Error> {
+        while let Some(request) = Request::read(connection)? {
+            self.handle(request).write(connection)?;
+        }
+
+        Ok(())
+    }
+
+    /// Handle a single request.
+    ///
+    /// Requests which cannot be signed are answered with a `RemoteError`.
+    pub fn handle(&mut self, request: Request) -> Response {
+        match request {
+            Request::SignProposal(request) => match self.sign(request) {
+                Ok(signed) => Response::SignedProposal(SignedProposalResponse {
+                    proposal: signed.proposal,
+                    err: None,
+                }),
+                Err(err) => Response::SignedProposal(SignedProposalResponse {
+                    proposal: None,
+                    err: Some(err),
+                }),
+            },
+            Request::SignVote(request) => match self.sign(request) {
+                Ok(signed) => Response::SignedVote(SignedVoteResponse {
+                    vote: signed.vote,
+                    err: None,
+                }),
+                Err(err) => Response::SignedVote(SignedVoteResponse {
+                    vote: None,
+                    err: Some(err),
+                }),
+            },
+            Request::ShowPublicKey(_) => {
+                Response::PublicKey(PubKeyResponse::from(PublicKey::from(self.public_key)))
+            }
+            Request::ReplyPing(_) => Response::Ping(PingResponse {}),
+        }
+    }
+
+    /// Validate the given message, check it against the last sign state, and sign it
+    fn sign<T: SignableMsg + Clone>(&mut self, mut msg: T) -> Result<T, RemoteError> {
+        msg.validate()
+            .map_err(|e| RemoteError::signer_error(e.to_string()))?;
+
+        // A message which was already signed gets the same signature again
+        if let Some(signature) = self.last_sign_state.check(self.chain_id, &mut msg)? {
+            msg.set_signature(&signature);
+            return Ok(msg);
+        }
+
+        let consensus_state = msg
+            .consensus_state()
+            .ok_or_else(|| RemoteError::signer_error("invalid consensus state"))?;
+
+        let sign_bytes = last_sign_state::sign_bytes(&msg, self.chain_id)?;
+
+        let signature = self
+            .signer
+            .try_sign(&sign_bytes)
+            .map_err(|e| RemoteError::signer_error(e.to_string()))?;
+
+        // Only record the new state once signing succeeded, so that a signer
+        // error doesn't prevent signing this message again
+        self.last_sign_state
+            .update(&consensus_state, sign_bytes, &signature)?;
+
+        msg.set_signature(&signature);
+
+        Ok(msg)
+    }
+}
@@ -1,8 +1,11 @@
-//! Tendermint RPC: JSONRPC over HTTP support
+//! Tendermint RPC: JSONRPC over HTTP support, over TCP (optionally secured
+//! with TLS) or UNIX domain sockets
 //!
 //! Wraps the RPC API described at: <https://tendermint.com/rpc/>
 
+pub mod batch;
 mod client;
+mod connection;
 pub mod endpoint;
 pub mod error;
 // TODO(ismail): document fields or re-use the abci types
@@ -10,11 +13,25 @@ pub mod error;
 pub mod event_listener;
 mod id;
 mod method;
+pub mod query;
 pub mod request;
 pub mod response;
+pub mod subscription;
+pub mod transport;
+pub mod url;
 mod version;
 
 pub use self::{
-    client::Client, error::Error, id::Id, method::Method, request::Request, response::Response,
+    batch::Batch,
+    client::{Client, Retry},
+    connection::TlsConfig,
+    error::Error,
+    id::Id,
+    method::Method,
+    query::Query,
+    request::Request,
+    response::Response,
+    subscription::SubscriptionClient,
+    url::Url,
     version::Version,
 };
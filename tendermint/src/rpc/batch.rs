@@ -0,0 +1,182 @@
+//! JSONRPC batches: requests of any kind sent in a single call, whose
+//! responses are matched to the requests by their ID
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), tendermint::rpc::Error> {
+//! use tendermint::rpc::{endpoint::{commit, validators}, Batch, Client};
+//!
+//! let client = Client::new("tcp://127.0.0.1:26657".parse().unwrap());
+//!
+//! let mut batch = Batch::new();
+//! let commit = batch.add(commit::Request::new(10u64.into()));
+//! let validators = batch.add(validators::Request::new(10u64.into()));
+//!
+//! let responses = client.batch(batch).await?;
+//! let signed_header = responses.get(&commit)?.signed_header;
+//! let validators = responses.get(&validators)?.validators;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::rpc::{request, response, Error, Id, Request};
+use std::{collections::BTreeMap, marker::PhantomData};
+
+/// Batch of requests, sent with [`Client::batch`]
+///
+/// [`Client::batch`]: ../struct.Client.html#method.batch
+#[derive(Clone, Debug, Default)]
+pub struct Batch {
+    requests: Vec<serde_json::Value>,
+    non_idempotent: bool,
+}
+
+impl Batch {
+    /// Create an empty batch
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a request to the batch, returning the handle of its response
+    pub fn add<R: Request>(&mut self, request: R) -> Handle<R> {
+        let wrapper = request::Wrapper::new(request);
+        let id = wrapper.id().clone();
+        self.non_idempotent |= !wrapper.method().is_idempotent();
+
+        self.requests
+            .push(serde_json::to_value(&wrapper).expect("request serialization failed"));
+
+        Handle {
+            id,
+            request: PhantomData,
+        }
+    }
+
+    /// Number of requests in the batch
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    /// Is the batch empty?
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    /// Can this batch be sent again without further effect, ie. are all of its
+    /// requests idempotent?
+    pub fn is_idempotent(&self) -> bool {
+        !self.non_idempotent
+    }
+
+    /// Serialize this batch as a JSON array of requests
+    pub fn into_json(self) -> String {
+        serde_json::to_string_pretty(&self.requests).unwrap()
+    }
+}
+
+/// Handle of the response to a request of a batch
+#[derive(Debug)]
+pub struct Handle<R> {
+    id: Id,
+    request: PhantomData<fn() -> R>,
+}
+
+impl<R> Handle<R> {
+    /// Get the JSONRPC ID of the request
+    pub fn id(&self) -> &Id {
+        &self.id
+    }
+}
+
+/// Responses to a batch of requests
+#[derive(Clone, Debug)]
+pub struct Responses {
+    responses: BTreeMap<Id, serde_json::Value>,
+}
+
+impl Responses {
+    /// Parse the JSON array of responses to a batch
+    pub fn from_string(responses: impl AsRef<[u8]>) -> Result<Self, Error> {
+        let responses: serde_json::Value =
+            serde_json::from_slice(responses.as_ref()).map_err(Error::parse_error)?;
+
+        let responses = match responses {
+            serde_json::Value::Array(responses) => responses,
+            // Errors concerning the batch as a whole come as a single response
+            response => {
+                let wrapper: response::Wrapper<serde_json::Value> =
+                    serde_json::from_value(response).map_err(Error::parse_error)?;
+                wrapper.into_result()?;
+                return Err(Error::parse_error("expected an array of responses"));
+            }
+        };
+
+        let mut by_id = BTreeMap::new();
+
+        for response in responses {
+            let id =
+                serde_json::from_value::<Id>(response["id"].clone()).map_err(Error::parse_error)?;
+            by_id.insert(id, response);
+        }
+
+        Ok(Self { responses: by_id })
+    }
+
+    /// Get the response to the request with the given handle
+    pub fn get<R: Request>(&self, handle: &Handle<R>) -> Result<R::Response, Error> {
+        let response = self.responses.get(&handle.id).ok_or_else(|| {
+            Error::parse_error(format!("missing response to request {:?}", handle.id))
+        })?;
+
+        let wrapper: response::Wrapper<R::Response> =
+            serde_json::from_value(response.clone()).map_err(Error::parse_error)?;
+        wrapper.into_result()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::endpoint::{abci_info, health};
+    use serde_json::json;
+
+    #[test]
+    fn match_responses_by_id() {
+        let mut batch = Batch::new();
+        let health = batch.add(health::Request);
+        let abci_info = batch.add(abci_info::Request);
+        assert_eq!(batch.len(), 2);
+
+        // Responses may come in any order
+        let responses = json!([
+            {
+                "jsonrpc": "2.0",
+                "id": abci_info.id(),
+                "error": {"code": -32603, "message": "Internal error", "data": "no app"}
+            },
+            {"jsonrpc": "2.0", "id": health.id(), "result": {}}
+        ]);
+
+        let responses = Responses::from_string(responses.to_string()).unwrap();
+        assert!(responses.get(&health).is_ok());
+        assert_eq!(
+            responses.get(&abci_info).unwrap_err().data(),
+            Some("no app")
+        );
+
+        let mut other_batch = Batch::new();
+        let missing = other_batch.add(health::Request);
+        assert!(responses.get(&missing).is_err());
+    }
+
+    #[test]
+    fn batch_errors() {
+        let response = json!({
+            "jsonrpc": "2.0",
+            "id": "",
+            "error": {"code": -32700, "message": "Parse error", "data": "invalid batch"}
+        });
+
+        let error = Responses::from_string(response.to_string()).unwrap_err();
+        assert_eq!(error.data(), Some("invalid batch"));
+    }
+}
@@ -3,25 +3,78 @@
 use crate::{
     abci::{self, Transaction},
     block::Height,
+    evidence::Evidence,
     net,
-    rpc::{endpoint::*, Error, Request, Response},
-    Genesis,
+    rpc::{
+        batch::{Batch, Responses},
+        connection::TlsConfig,
+        endpoint::*,
+        subscription::Backoff,
+        transport::{HttpTransport, Transport},
+        url::Url,
+        Error, Request, Response,
+    },
+    validator, Genesis,
 };
-use bytes::buf::ext::BufExt;
-use hyper::header;
+use std::{cmp, time::Duration};
 
 /// Tendermint RPC client.
 ///
-/// Presently supports JSONRPC via HTTP.
-pub struct Client {
-    /// Address of the RPC server
-    address: net::Address,
+/// Sends JSONRPC requests over a [`Transport`], by default over HTTP.
+///
+/// [`Transport`]: transport/trait.Transport.html
+pub struct Client<T = HttpTransport> {
+    /// Transport of the requests
+    transport: T,
+
+    /// Timeout of each attempt at a request, if any
+    timeout: Option<Duration>,
+
+    /// Policy for retrying failed attempts
+    retry: Retry,
 }
 
-impl Client {
-    /// Create a new Tendermint RPC client, connecting to the given address
+impl Client<HttpTransport> {
+    /// Create a new Tendermint RPC client, connecting to the given TCP or UNIX
+    /// socket address
     pub fn new(address: net::Address) -> Self {
-        Self { address }
+        Self::with_transport(HttpTransport::new(address))
+    }
+
+    /// Create a new Tendermint RPC client for the given `http://` or
+    /// `https://` URL, authenticating the server with the given TLS settings
+    /// over `https://`
+    pub fn from_url(url: &Url, tls: TlsConfig) -> Result<Self, Error> {
+        Ok(Self::with_transport(HttpTransport::from_url(url, tls)?))
+    }
+}
+
+impl<T: Transport> Client<T> {
+    /// Create a new Tendermint RPC client sending requests over the given
+    /// transport, without timeout nor retries
+    pub fn with_transport(transport: T) -> Self {
+        Self {
+            transport,
+            timeout: None,
+            retry: Retry::default(),
+        }
+    }
+
+    /// Give up on attempts at a request after the given timeout
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Retry failed attempts at a request according to the given policy
+    pub fn retry(mut self, retry: Retry) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Borrow the transport of the requests
+    pub fn transport(&self) -> &T {
+        &self.transport
     }
 
     /// `/abci_info`: get information about the ABCI application.
@@ -108,12 +161,82 @@ impl Client {
         self.perform(broadcast::tx_commit::Request::new(tx)).await
     }
 
+    /// `/broadcast_evidence`: broadcast evidence of malfeasance by a
+    /// validator, returning the hash of the evidence.
+    pub async fn broadcast_evidence(
+        &self,
+        evidence: Evidence,
+    ) -> Result<broadcast_evidence::Response, Error> {
+        self.perform(broadcast_evidence::Request::new(evidence))
+            .await
+    }
+
+    /// `/check_tx`: check a transaction with the application, without adding
+    /// it to the mempool.
+    pub async fn check_tx(&self, tx: Transaction) -> Result<check_tx::Response, Error> {
+        self.perform(check_tx::Request::new(tx)).await
+    }
+
     /// `/commit`: get block commit at a given height.
     pub async fn commit(&self, height: impl Into<Height>) -> Result<commit::Response, Error> {
         self.perform(commit::Request::new(height.into())).await
     }
 
+    /// `/consensus_params`: get the consensus parameters at a given height.
+    pub async fn consensus_params(
+        &self,
+        height: impl Into<Height>,
+    ) -> Result<consensus_params::Response, Error> {
+        self.perform(consensus_params::Request::new(height.into()))
+            .await
+    }
+
+    /// `/consensus_params`: get the consensus parameters of the latest block.
+    pub async fn latest_consensus_params(&self) -> Result<consensus_params::Response, Error> {
+        self.perform(consensus_params::Request::default()).await
+    }
+
+    /// `/consensus_state`: get a summary of the consensus state.
+    pub async fn consensus_state(&self) -> Result<consensus_state::Response, Error> {
+        self.perform(consensus_state::Request).await
+    }
+
+    /// `/dump_consensus_state`: get the complete consensus state, including
+    /// the consensus states of the peers.
+    pub async fn dump_consensus_state(&self) -> Result<dump_consensus_state::Response, Error> {
+        self.perform(dump_consensus_state::Request).await
+    }
+
+    /// `/tx`: find a committed transaction by its hash, optionally with a
+    /// proof of its inclusion in its block.
+    pub async fn tx(
+        &self,
+        hash: abci::transaction::Hash,
+        prove: bool,
+    ) -> Result<tx::Response, Error> {
+        self.perform(tx::Request::new(hash, prove)).await
+    }
+
+    /// `/tx_search`: search for committed transactions by the events they
+    /// emitted, eg. `tx.height = 5`.
+    ///
+    /// Results are paginated, starting at page 1.
+    pub async fn tx_search(
+        &self,
+        query: impl Into<String>,
+        prove: bool,
+        page: u32,
+        per_page: u8,
+        order: tx_search::Order,
+    ) -> Result<tx_search::Response, Error> {
+        self.perform(tx_search::Request::new(query, prove, page, per_page, order))
+            .await
+    }
+
     /// `/validators`: get validators a given height.
+    ///
+    /// Only returns the first page of validators: see `validator_set` to get
+    /// all of them.
     pub async fn validators<H>(&self, height: H) -> Result<validators::Response, Error>
     where
         H: Into<Height>,
@@ -121,6 +244,42 @@ impl Client {
         self.perform(validators::Request::new(height.into())).await
     }
 
+    /// `/validators`: get the complete validator set at a given height,
+    /// fetching all its pages.
+    pub async fn validator_set<H>(&self, height: H) -> Result<validator::Set, Error>
+    where
+        H: Into<Height>,
+    {
+        let height = height.into();
+        let mut validators = vec![];
+        let mut page = 1;
+
+        loop {
+            let response = self
+                .perform(validators::Request::paginated(
+                    height,
+                    page,
+                    validators::MAX_PER_PAGE,
+                ))
+                .await?;
+
+            if response.validators.is_empty() {
+                break;
+            }
+
+            validators.extend(response.validators);
+
+            // Nodes which don't paginate validators return all of them at once
+            if response.total == 0 || validators.len() >= response.total as usize {
+                break;
+            }
+
+            page += 1;
+        }
+
+        Ok(validator::Set::new(validators))
+    }
+
     /// `/commit`: get the latest block commit
     pub async fn latest_commit(&self) -> Result<commit::Response, Error> {
         self.perform(commit::Request::default()).await
@@ -144,47 +303,97 @@ impl Client {
         self.perform(net_info::Request).await
     }
 
+    /// `/num_unconfirmed_txs`: get the number and total size of the
+    /// transactions in the mempool.
+    pub async fn num_unconfirmed_txs(&self) -> Result<num_unconfirmed_txs::Response, Error> {
+        self.perform(num_unconfirmed_txs::Request).await
+    }
+
+    /// `/unconfirmed_txs`: get at most `limit` transactions from the mempool.
+    pub async fn unconfirmed_txs(&self, limit: u32) -> Result<unconfirmed_txs::Response, Error> {
+        self.perform(unconfirmed_txs::Request::new(limit)).await
+    }
+
     /// `/status`: get Tendermint status including node info, pubkey, latest
     /// block hash, app hash, block height and time.
     pub async fn status(&self) -> Result<status::Response, Error> {
         self.perform(status::Request).await
     }
 
-    /// Perform a request against the RPC endpoint
+    /// Perform a request against the RPC endpoint.
+    ///
+    /// Attempts which fail to get a response from the node, or time out, are
+    /// retried according to the retry policy of the client. Errors returned by
+    /// the node are not, and neither are requests whose method isn't
+    /// idempotent, such as broadcasting a transaction.
     pub async fn perform<R>(&self, request: R) -> Result<R::Response, Error>
     where
         R: Request,
     {
-        let request_body = request.into_json();
-
-        let (host, port) = match &self.address {
-            net::Address::Tcp { host, port, .. } => (host, port),
-            other => {
-                return Err(Error::invalid_params(&format!(
-                    "invalid RPC address: {:?}",
-                    other
-                )))
-            }
+        let idempotent = request.method().is_idempotent();
+        R::Response::from_string(self.send(request.into_json(), idempotent).await?)
+    }
+
+    /// Perform a batch of requests against the RPC endpoint, in a single call.
+    ///
+    /// Attempts are retried as with [`perform`](#method.perform), unless the
+    /// batch contains a request which isn't idempotent. The responses to the
+    /// requests of the batch are then obtained from their handles.
+    pub async fn batch(&self, batch: Batch) -> Result<Responses, Error> {
+        let idempotent = batch.is_idempotent();
+        Responses::from_string(self.send(batch.into_json(), idempotent).await?)
+    }
+
+    async fn send(&self, request: String, idempotent: bool) -> Result<String, Error> {
+        let max_attempts = if idempotent {
+            self.retry.max_attempts
+        } else {
+            1
         };
 
-        let mut request = hyper::Request::builder()
-            .method("POST")
-            .uri(&format!("http://{}:{}/", host, port))
-            .body(hyper::Body::from(request_body.into_bytes()))?;
-
-        {
-            let headers = request.headers_mut();
-            headers.insert(header::CONTENT_TYPE, "application/json".parse().unwrap());
-            headers.insert(
-                header::USER_AGENT,
-                format!("tendermint.rs/{}", env!("CARGO_PKG_VERSION"))
-                    .parse()
-                    .unwrap(),
-            );
+        let mut delay = self.retry.backoff.initial;
+        let mut attempts = 1;
+
+        loop {
+            match self.attempt(request.clone()).await {
+                Ok(response) => return Ok(response),
+                Err(error) if attempts >= max_attempts => return Err(error),
+                Err(_) => {
+                    tokio::time::delay_for(delay).await;
+                    delay = cmp::min(delay * 2, self.retry.backoff.max);
+                    attempts += 1;
+                }
+            }
+        }
+    }
+
+    async fn attempt(&self, request: String) -> Result<String, Error> {
+        match self.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, self.transport.request(request))
+                .await
+                .map_err(|_| Error::http_error(format!("request timed out after {:?}", timeout)))?,
+            None => self.transport.request(request).await,
+        }
+    }
+}
+
+/// Policy for retrying the attempts at a request which failed to get a
+/// response from the node, if the request is idempotent
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Retry {
+    /// Maximum number of attempts, including the first one
+    pub max_attempts: u32,
+
+    /// Delays between attempts
+    pub backoff: Backoff,
+}
+
+impl Default for Retry {
+    /// Make a single attempt
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            backoff: Backoff::default(),
         }
-        let http_client = hyper::Client::builder().build_http();
-        let response = http_client.request(request).await?;
-        let response_body = hyper::body::aggregate(response.into_body()).await?;
-        R::Response::from_reader(response_body.reader())
     }
 }
@@ -0,0 +1,250 @@
+//! Connections to RPC servers, over TCP (optionally secured with TLS) or
+//! UNIX domain sockets
+
+use crate::{
+    net,
+    rpc::{
+        url::{self, Url},
+        Error,
+    },
+};
+use futures::future::BoxFuture;
+use hyper::{
+    client::connect::{Connected, Connection},
+    Uri,
+};
+use rustls::ClientConfig;
+use std::{
+    io,
+    net::IpAddr,
+    path::PathBuf,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::{TcpStream, UnixStream},
+};
+use tokio_rustls::{client::TlsStream, TlsConnector};
+use tower_service::Service;
+use webpki::DNSNameRef;
+
+/// TLS settings of RPC clients, ie. the CA certificates trusted to
+/// authenticate servers
+#[derive(Clone)]
+pub struct TlsConfig {
+    client_config: Arc<ClientConfig>,
+}
+
+impl TlsConfig {
+    /// Only trust the given (PEM encoded) CA certificates, eg. those of a
+    /// private CA
+    pub fn from_ca_roots_pem(pem: &[u8]) -> Result<Self, Error> {
+        let mut client_config = ClientConfig::new();
+
+        let (valid, _) = client_config
+            .root_store
+            .add_pem_file(&mut io::Cursor::new(pem))
+            .map_err(|_| Error::invalid_params("invalid PEM encoded CA certificates"))?;
+
+        if valid == 0 {
+            return Err(Error::invalid_params("no valid CA certificate"));
+        }
+
+        Ok(Self {
+            client_config: Arc::new(client_config),
+        })
+    }
+}
+
+impl Default for TlsConfig {
+    /// Trust the Mozilla root certificates
+    fn default() -> Self {
+        let mut client_config = ClientConfig::new();
+        client_config
+            .root_store
+            .add_server_trust_anchors(&webpki_roots::TLS_SERVER_ROOTS);
+
+        Self {
+            client_config: Arc::new(client_config),
+        }
+    }
+}
+
+/// RPC server to connect to
+#[derive(Clone)]
+pub(crate) enum Peer {
+    /// Server listening on a TCP port
+    Tcp {
+        host: String,
+        port: u16,
+        tls: Option<TlsConfig>,
+    },
+
+    /// Server listening on a UNIX domain socket
+    Unix { path: PathBuf },
+}
+
+impl Peer {
+    /// Server at the given address, connected to without TLS
+    pub(crate) fn from_address(address: net::Address) -> Self {
+        match address {
+            net::Address::Tcp { host, port, .. } => Peer::Tcp {
+                host,
+                port,
+                tls: None,
+            },
+            net::Address::Unix { path } => Peer::Unix { path },
+        }
+    }
+
+    /// Server at the given URL, connected to with the given TLS settings if
+    /// its scheme is secure.
+    ///
+    /// Servers connected to over TLS must be addressed by a DNS name, as
+    /// certificates are only checked against DNS names.
+    pub(crate) fn from_url(url: &Url, tls: TlsConfig) -> Result<Self, Error> {
+        if !url.scheme.is_secure() {
+            return Ok(Peer::Tcp {
+                host: url.host.clone(),
+                port: url.port,
+                tls: None,
+            });
+        }
+
+        if url.host.parse::<IpAddr>().is_ok() {
+            return Err(Error::invalid_params(&format!(
+                "TLS servers must be addressed by a DNS name, not an IP address: {}",
+                url
+            )));
+        }
+
+        Ok(Peer::Tcp {
+            host: url.host.clone(),
+            port: url.port,
+            tls: Some(tls),
+        })
+    }
+
+    /// Authority of the server, as used in the `Host` header of requests
+    pub(crate) fn authority(&self) -> String {
+        match self {
+            Peer::Tcp { host, port, .. } => url::authority(host, *port),
+            Peer::Unix { .. } => "localhost".to_owned(),
+        }
+    }
+
+    /// Open a new connection to the server
+    pub(crate) async fn connect(&self) -> io::Result<Stream> {
+        match self {
+            Peer::Tcp { host, port, tls } => {
+                let stream = TcpStream::connect((host.as_str(), *port)).await?;
+
+                match tls {
+                    None => Ok(Stream::Tcp(stream)),
+                    Some(tls) => {
+                        let domain = DNSNameRef::try_from_ascii_str(host).map_err(|_| {
+                            io::Error::new(
+                                io::ErrorKind::InvalidInput,
+                                format!("invalid TLS server name: {}", host),
+                            )
+                        })?;
+
+                        let stream = TlsConnector::from(tls.client_config.clone())
+                            .connect(domain, stream)
+                            .await?;
+
+                        Ok(Stream::Tls(Box::new(stream)))
+                    }
+                }
+            }
+            Peer::Unix { path } => Ok(Stream::Unix(UnixStream::connect(path).await?)),
+        }
+    }
+}
+
+/// Connector of `hyper` clients, opening connections to a given server
+/// whatever the URI of the requests
+#[derive(Clone)]
+pub(crate) struct Connector {
+    peer: Peer,
+}
+
+impl Connector {
+    pub(crate) fn new(peer: Peer) -> Self {
+        Self { peer }
+    }
+}
+
+impl Service<Uri> for Connector {
+    type Response = Stream;
+    type Error = io::Error;
+    type Future = BoxFuture<'static, io::Result<Stream>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _uri: Uri) -> Self::Future {
+        let peer = self.peer.clone();
+        Box::pin(async move { peer.connect().await })
+    }
+}
+
+/// Connection to an RPC server
+pub(crate) enum Stream {
+    Tcp(TcpStream),
+    Tls(Box<TlsStream<TcpStream>>),
+    Unix(UnixStream),
+}
+
+impl Connection for Stream {
+    fn connected(&self) -> Connected {
+        Connected::new()
+    }
+}
+
+impl AsyncRead for Stream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Stream::Tcp(stream) => Pin::new(stream).poll_read(cx, buf),
+            Stream::Tls(stream) => Pin::new(stream).poll_read(cx, buf),
+            Stream::Unix(stream) => Pin::new(stream).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for Stream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Stream::Tcp(stream) => Pin::new(stream).poll_write(cx, buf),
+            Stream::Tls(stream) => Pin::new(stream).poll_write(cx, buf),
+            Stream::Unix(stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Stream::Tcp(stream) => Pin::new(stream).poll_flush(cx),
+            Stream::Tls(stream) => Pin::new(stream).poll_flush(cx),
+            Stream::Unix(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Stream::Tcp(stream) => Pin::new(stream).poll_shutdown(cx),
+            Stream::Tls(stream) => Pin::new(stream).poll_shutdown(cx),
+            Stream::Unix(stream) => Pin::new(stream).poll_shutdown(cx),
+        }
+    }
+}
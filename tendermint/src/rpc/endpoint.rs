@@ -6,10 +6,21 @@ pub mod block;
 pub mod block_results;
 pub mod blockchain;
 pub mod broadcast;
+pub mod broadcast_evidence;
+pub mod check_tx;
 pub mod commit;
+pub mod consensus_params;
+pub mod consensus_state;
+pub mod dump_consensus_state;
 pub mod genesis;
 pub mod health;
 pub mod net_info;
+pub mod num_unconfirmed_txs;
 pub mod status;
 pub mod subscribe;
+pub mod tx;
+pub mod tx_search;
+pub mod unconfirmed_txs;
+pub mod unsubscribe;
+pub mod unsubscribe_all;
 pub mod validators;
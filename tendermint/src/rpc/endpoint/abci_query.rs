@@ -3,9 +3,11 @@
 use crate::{
     abci::{Code, Log, Path},
     block,
+    error::{Error, Kind},
     merkle::proof::Proof,
     rpc, serializers,
 };
+use anomaly::{fail, format_err};
 use serde::{Deserialize, Serialize};
 
 /// Query the ABCI application for information
@@ -95,3 +97,35 @@ pub struct AbciQuery {
     #[serde(default = "String::new")]
     pub codespace: String,
 }
+
+impl AbciQuery {
+    /// Verify the value of these query results against the app hash of a
+    /// header verified by a light client, using their Merkle proof (which is
+    /// only returned for queries made with `prove` set).
+    ///
+    /// `store` is the name of the queried (Cosmos SDK) store. Since the app hash
+    /// committing to the state resulting from the block at height `H` is the one
+    /// of the header at height `H + 1`, the header must be the one following the
+    /// height of these results.
+    pub fn verify(&self, store: &str, header: &block::Header) -> Result<(), Error> {
+        if header.height != self.height.increment() {
+            fail!(
+                Kind::InvalidProof,
+                "expected header at height {}, got {}",
+                self.height.increment(),
+                header.height
+            );
+        }
+
+        let proof = self
+            .proof
+            .as_ref()
+            .ok_or_else(|| format_err!(Kind::InvalidProof, "no proof in query results"))?;
+
+        proof.verify_value(
+            &header.app_hash,
+            &[store.as_bytes(), &self.key],
+            &self.value,
+        )
+    }
+}
@@ -53,3 +53,14 @@ pub struct Response {
 }
 
 impl rpc::Response for Response {}
+
+impl Response {
+    /// Deterministic results of the transactions of the block, as committed
+    /// to by the `last_results_hash` of the header of the next block.
+    ///
+    /// These are the codes and data of the results: their gas, logs and
+    /// events are not committed to.
+    pub fn results(&self) -> abci::AbciResults {
+        self.txs_results.as_deref().unwrap_or_default().into()
+    }
+}
@@ -0,0 +1,38 @@
+//! `/broadcast_evidence`: submits evidence of malfeasance by a validator.
+
+use crate::{evidence::Evidence, rpc, serializers};
+use serde::{Deserialize, Serialize};
+
+/// `/broadcast_evidence`: submits evidence of malfeasance by a validator.
+///
+/// The evidence is verified by the node, then gossiped to its peers.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Request {
+    /// Evidence to broadcast
+    pub evidence: Evidence,
+}
+
+impl Request {
+    /// Create a new evidence broadcast RPC request
+    pub fn new(evidence: Evidence) -> Request {
+        Request { evidence }
+    }
+}
+
+impl rpc::Request for Request {
+    type Response = Response;
+
+    fn method(&self) -> rpc::Method {
+        rpc::Method::BroadcastEvidence
+    }
+}
+
+/// Response from `/broadcast_evidence`
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Response {
+    /// Hash of the evidence
+    #[serde(with = "serializers::bytes::base64string")]
+    pub hash: Vec<u8>,
+}
+
+impl rpc::Response for Response {}
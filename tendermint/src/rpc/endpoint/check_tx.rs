@@ -0,0 +1,71 @@
+//! `/check_tx`: checks a transaction with the application, without adding it
+//! to the mempool.
+
+use crate::{
+    abci::{self, responses::Codespace, Code, Data, Event, Gas, Info, Log, Transaction},
+    rpc,
+};
+use serde::{Deserialize, Serialize};
+
+/// `/check_tx`: checks a transaction with the application, without adding it
+/// to the mempool.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Request {
+    /// Transaction to check
+    pub tx: Transaction,
+}
+
+impl Request {
+    /// Create a new transaction check RPC request
+    pub fn new(tx: Transaction) -> Request {
+        Request { tx }
+    }
+}
+
+impl rpc::Request for Request {
+    type Response = Response;
+
+    fn method(&self) -> rpc::Method {
+        rpc::Method::CheckTx
+    }
+}
+
+/// Response from `/check_tx`, ie. the `ResponseCheckTx` of the application.
+///
+/// Empty fields are omitted by the node.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Response {
+    /// ABCI application response code
+    #[serde(default)]
+    pub code: Code,
+
+    /// ABCI application data
+    #[serde(default, with = "abci::data::base64")]
+    pub data: Data,
+
+    /// ABCI log data (nondeterministic)
+    #[serde(default)]
+    pub log: Log,
+
+    /// ABCI info (nondeterministic)
+    #[serde(default)]
+    pub info: Info,
+
+    /// Amount of gas wanted
+    #[serde(default)]
+    pub gas_wanted: Gas,
+
+    /// Amount of gas used
+    #[serde(default)]
+    pub gas_used: Gas,
+
+    /// Events
+    #[serde(default)]
+    pub events: Vec<Event>,
+
+    /// Codespace
+    #[serde(default)]
+    pub codespace: Codespace,
+}
+
+impl rpc::Response for Response {}
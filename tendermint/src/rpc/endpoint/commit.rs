@@ -16,6 +16,13 @@ impl Request {
             height: Some(height),
         }
     }
+
+    /// Height of the requested commit.
+    ///
+    /// If no height is provided, the latest commit is requested.
+    pub fn height(&self) -> Option<block::Height> {
+        self.height
+    }
 }
 
 impl rpc::Request for Request {
@@ -0,0 +1,64 @@
+//! `/consensus_params` endpoint JSONRPC wrapper
+
+use crate::{
+    block, consensus,
+    error::{Error, Kind},
+    rpc,
+};
+use anomaly::fail;
+use serde::{Deserialize, Serialize};
+
+/// Get the consensus parameters at a given height
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Request {
+    /// Height at which to get the consensus parameters.
+    ///
+    /// If no height is provided, it will fetch those of the latest block.
+    height: Option<block::Height>,
+}
+
+impl Request {
+    /// Create a new request for the consensus parameters at a given height
+    pub fn new(height: block::Height) -> Self {
+        Self {
+            height: Some(height),
+        }
+    }
+}
+
+impl rpc::Request for Request {
+    type Response = Response;
+
+    fn method(&self) -> rpc::Method {
+        rpc::Method::ConsensusParams
+    }
+}
+
+/// Consensus parameters responses
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Response {
+    /// Height of the block
+    pub block_height: block::Height,
+
+    /// Consensus parameters at this height
+    pub consensus_params: consensus::Params,
+}
+
+impl Response {
+    /// Verify the consensus parameters against the `consensus_hash` of the
+    /// (verified) header at the same height
+    pub fn verify(&self, header: &block::Header) -> Result<(), Error> {
+        if self.block_height != header.height {
+            fail!(
+                Kind::InvalidBlock,
+                "consensus params of block {} can't be verified against header {}",
+                self.block_height,
+                header.height
+            );
+        }
+
+        self.consensus_params.verify(header)
+    }
+}
+
+impl rpc::Response for Response {}
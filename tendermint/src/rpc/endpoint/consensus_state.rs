@@ -0,0 +1,129 @@
+//! `/consensus_state` endpoint JSONRPC wrapper
+
+use crate::{
+    block, consensus,
+    rpc::{self, event_listener::ProposerInfo},
+    serializers, Hash, Time,
+};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// Get a summary of the consensus state of the node
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Request;
+
+impl rpc::Request for Request {
+    type Response = Response;
+
+    fn method(&self) -> rpc::Method {
+        rpc::Method::ConsensusState
+    }
+}
+
+/// Consensus state responses
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Response {
+    /// Summary of the current round
+    pub round_state: RoundState,
+}
+
+impl rpc::Response for Response {}
+
+/// Summary of the current consensus round
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RoundState {
+    /// Height, round and step of the consensus
+    #[serde(rename = "height/round/step")]
+    pub height_round_step: HeightRoundStep,
+
+    /// Start time of the current height
+    pub start_time: Time,
+
+    /// Hash of the proposed block, if any
+    #[serde(deserialize_with = "serializers::parse_non_empty_hash")]
+    pub proposal_block_hash: Option<Hash>,
+
+    /// Hash of the locked block, if any
+    #[serde(deserialize_with = "serializers::parse_non_empty_hash")]
+    pub locked_block_hash: Option<Hash>,
+
+    /// Hash of the last block which got a polka, if any
+    #[serde(deserialize_with = "serializers::parse_non_empty_hash")]
+    pub valid_block_hash: Option<Hash>,
+
+    /// Votes of the rounds of the current height
+    pub height_vote_set: Vec<RoundVotes>,
+
+    /// Proposer of the current round
+    pub proposer: ProposerInfo,
+}
+
+/// Votes received in a round, as summarized by the node
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct RoundVotes {
+    /// Round of the votes
+    #[serde(with = "serializers::from_str")]
+    pub round: i64,
+
+    /// Prevotes, eg. `Vote{0:B5B3D40BE539 1262197/00/1(Prevote) ...}`, or
+    /// `nil-Vote` for validators which didn't prevote
+    pub prevotes: Vec<String>,
+
+    /// Bit array of the prevotes, eg. `BA{4:xx_x} 30/40 = 0.75`
+    pub prevotes_bit_array: String,
+
+    /// Precommits, eg. `Vote{0:B5B3D40BE539 1262197/00/2(Precommit) ...}`,
+    /// or `nil-Vote` for validators which didn't precommit
+    pub precommits: Vec<String>,
+
+    /// Bit array of the precommits, eg. `BA{4:xx_x} 30/40 = 0.75`
+    pub precommits_bit_array: String,
+}
+
+/// Height, round and step of a consensus round, (de)serialized as a
+/// `height/round/step` string, eg. `410745/0/4`
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct HeightRoundStep {
+    /// Current block height
+    pub height: block::Height,
+
+    /// Current consensus round
+    pub round: i64,
+
+    /// Current step of the round
+    pub step: consensus::RoundStep,
+}
+
+impl fmt::Display for HeightRoundStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}/{}", self.height, self.round, self.step)
+    }
+}
+
+impl Serialize for HeightRoundStep {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for HeightRoundStep {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hrs = String::deserialize(deserializer)?;
+        let invalid = || D::Error::custom(format!("invalid height/round/step: {}", hrs));
+        let parts = hrs.split('/').collect::<Vec<_>>();
+
+        if parts.len() != 3 {
+            return Err(invalid());
+        }
+
+        Ok(HeightRoundStep {
+            height: parts[0].parse().map_err(|_| invalid())?,
+            round: parts[1].parse().map_err(|_| invalid())?,
+            step: parts[2]
+                .parse()
+                .ok()
+                .and_then(consensus::RoundStep::from_u8)
+                .ok_or_else(invalid)?,
+        })
+    }
+}
@@ -0,0 +1,128 @@
+//! `/dump_consensus_state` endpoint JSONRPC wrapper
+
+use super::consensus_state::RoundVotes;
+use crate::{block, consensus, rpc, serializers, validator, Time};
+use serde::{Deserialize, Serialize};
+
+/// Get the complete consensus state of the node, including the state of its
+/// peers
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Request;
+
+impl rpc::Request for Request {
+    type Response = Response;
+
+    fn method(&self) -> rpc::Method {
+        rpc::Method::DumpConsensusState
+    }
+}
+
+/// Consensus state dump responses
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Response {
+    /// State of the current round
+    pub round_state: RoundState,
+
+    /// Consensus states of the peers
+    pub peers: Vec<PeerState>,
+}
+
+impl rpc::Response for Response {}
+
+/// State of the current consensus round.
+///
+/// Proposals and blocks under consideration are not included.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RoundState {
+    /// Current block height
+    pub height: block::Height,
+
+    /// Current consensus round
+    #[serde(with = "serializers::from_str")]
+    pub round: i64,
+
+    /// Current step of the round
+    pub step: consensus::RoundStep,
+
+    /// Start time of the current height
+    pub start_time: Time,
+
+    /// Time of the last commit
+    pub commit_time: Time,
+
+    /// Validators of the current height
+    pub validators: validator::Set,
+
+    /// Round of the locked block, or -1
+    #[serde(with = "serializers::from_str")]
+    pub locked_round: i64,
+
+    /// Round of the last block which got a polka, or -1
+    #[serde(with = "serializers::from_str")]
+    pub valid_round: i64,
+
+    /// Votes of the rounds of the current height
+    pub votes: Vec<RoundVotes>,
+
+    /// Round of the commit, or -1
+    #[serde(with = "serializers::from_str")]
+    pub commit_round: i64,
+
+    /// Validators of the previous height
+    pub last_validators: validator::Set,
+
+    /// Has the precommit timeout been triggered?
+    pub triggered_timeout_precommit: bool,
+}
+
+/// Consensus state of a peer
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PeerState {
+    /// Address of the peer, as `<node ID>@<host>:<port>`
+    pub node_address: String,
+
+    /// State of the peer
+    pub peer_state: PeerInfo,
+}
+
+/// State of a peer, as known by the node
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PeerInfo {
+    /// Current round of the peer
+    pub round_state: PeerRoundState,
+
+    /// Statistics of the messages received from the peer
+    pub stats: PeerStats,
+}
+
+/// Current consensus round of a peer
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PeerRoundState {
+    /// Block height of the peer
+    pub height: block::Height,
+
+    /// Consensus round of the peer
+    #[serde(with = "serializers::from_str")]
+    pub round: i64,
+
+    /// Step of the round of the peer
+    pub step: consensus::RoundStep,
+
+    /// Start time of the current height of the peer
+    pub start_time: Time,
+
+    /// Does the peer have the proposal of its round?
+    pub proposal: bool,
+}
+
+/// Statistics of the messages received from a peer
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PeerStats {
+    /// Number of votes received from the peer
+    #[serde(with = "serializers::from_str")]
+    pub votes: u64,
+
+    /// Number of block parts received from the peer
+    #[serde(with = "serializers::from_str")]
+    pub block_parts: u64,
+}
@@ -0,0 +1,19 @@
+//! `/num_unconfirmed_txs` endpoint JSONRPC wrapper
+
+use crate::rpc;
+use serde::{Deserialize, Serialize};
+
+pub use super::unconfirmed_txs::Response;
+
+/// Get the number and total size of the transactions in the mempool, without
+/// the transactions themselves
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Request;
+
+impl rpc::Request for Request {
+    type Response = Response;
+
+    fn method(&self) -> rpc::Method {
+        rpc::Method::NumUnconfirmedTxs
+    }
+}
@@ -0,0 +1,61 @@
+//! `/tx` endpoint JSONRPC wrapper
+
+use crate::{
+    abci::{self, transaction, Transaction},
+    block, rpc, serializers,
+};
+use serde::{Deserialize, Serialize};
+
+/// Find a committed transaction by its hash
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Request {
+    /// Hash of the transaction
+    #[serde(with = "serializers::bytes::base64string")]
+    hash: Vec<u8>,
+
+    /// Include a proof of the transaction's inclusion in its block
+    prove: bool,
+}
+
+impl Request {
+    /// Create a new request for the transaction with the given hash
+    pub fn new(hash: transaction::Hash, prove: bool) -> Self {
+        Self {
+            hash: hash.as_bytes().to_vec(),
+            prove,
+        }
+    }
+}
+
+impl rpc::Request for Request {
+    type Response = Response;
+
+    fn method(&self) -> rpc::Method {
+        rpc::Method::Tx
+    }
+}
+
+/// Committed transaction, with its `DeliverTx` result
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Response {
+    /// Hash of the transaction
+    pub hash: transaction::Hash,
+
+    /// Height of the block containing the transaction
+    pub height: block::Height,
+
+    /// Index of the transaction in its block
+    pub index: u32,
+
+    /// `DeliverTx` result
+    pub tx_result: abci::DeliverTx,
+
+    /// Transaction
+    pub tx: Transaction,
+
+    /// Proof of the transaction's inclusion in its block, if requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof: Option<transaction::Proof>,
+}
+
+impl rpc::Response for Response {}
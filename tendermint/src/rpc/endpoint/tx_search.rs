@@ -0,0 +1,79 @@
+//! `/tx_search` endpoint JSONRPC wrapper
+
+use crate::{rpc, serializers};
+use serde::{Deserialize, Serialize};
+
+pub use super::tx::Response as ResultTx;
+
+/// Search for committed transactions by the events they emitted
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Request {
+    /// Query on the events emitted by the transactions, eg. `tx.height = 5`
+    pub query: String,
+
+    /// Include proofs of the transactions' inclusion in their blocks
+    pub prove: bool,
+
+    /// Page of results to return, starting at 1
+    #[serde(with = "serializers::from_str")]
+    pub page: u32,
+
+    /// Number of results per page
+    #[serde(with = "serializers::from_str")]
+    pub per_page: u8,
+
+    /// Order of the results, by height and index
+    pub order_by: Order,
+}
+
+impl Request {
+    /// Create a new transaction search request
+    pub fn new(
+        query: impl Into<String>,
+        prove: bool,
+        page: u32,
+        per_page: u8,
+        order_by: Order,
+    ) -> Self {
+        Self {
+            query: query.into(),
+            prove,
+            page,
+            per_page,
+            order_by,
+        }
+    }
+}
+
+impl rpc::Request for Request {
+    type Response = Response;
+
+    fn method(&self) -> rpc::Method {
+        rpc::Method::TxSearch
+    }
+}
+
+/// Order of search results
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Order {
+    /// Ascending order
+    #[serde(rename = "asc")]
+    Ascending,
+
+    /// Descending order
+    #[serde(rename = "desc")]
+    Descending,
+}
+
+/// Transactions matching a search
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Response {
+    /// Page of matching transactions
+    pub txs: Vec<ResultTx>,
+
+    /// Total number of matching transactions
+    #[serde(with = "serializers::from_str")]
+    pub total_count: u32,
+}
+
+impl rpc::Response for Response {}
@@ -0,0 +1,66 @@
+//! `/unconfirmed_txs` endpoint JSONRPC wrapper
+
+use crate::{abci::Transaction, rpc, serializers};
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Number of transactions returned by default
+pub const DEFAULT_LIMIT: u32 = 30;
+
+/// Get the transactions waiting in the mempool
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Request {
+    /// Maximum number of transactions to return (the node caps it at 100)
+    #[serde(with = "serializers::from_str")]
+    pub limit: u32,
+}
+
+impl Request {
+    /// Create a new request for at most `limit` unconfirmed transactions
+    pub fn new(limit: u32) -> Self {
+        Self { limit }
+    }
+}
+
+impl Default for Request {
+    fn default() -> Self {
+        Self::new(DEFAULT_LIMIT)
+    }
+}
+
+impl rpc::Request for Request {
+    type Response = Response;
+
+    fn method(&self) -> rpc::Method {
+        rpc::Method::UnconfirmedTxs
+    }
+}
+
+/// Unconfirmed transactions responses
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Response {
+    /// Number of transactions returned
+    #[serde(with = "serializers::from_str")]
+    pub n_txs: u64,
+
+    /// Total number of transactions in the mempool
+    #[serde(with = "serializers::from_str")]
+    pub total: u64,
+
+    /// Total size of the transactions in the mempool, in bytes
+    #[serde(with = "serializers::from_str")]
+    pub total_bytes: u64,
+
+    /// Transactions returned, oldest first
+    #[serde(default, deserialize_with = "deserialize_txs")]
+    pub txs: Vec<Transaction>,
+}
+
+impl rpc::Response for Response {}
+
+/// Return an empty vec in the event `txs` is `null`
+fn deserialize_txs<'de, D>(deserializer: D) -> Result<Vec<Transaction>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::deserialize(deserializer)?.unwrap_or_default())
+}
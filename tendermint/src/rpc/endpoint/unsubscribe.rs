@@ -0,0 +1,32 @@
+//! `/unsubscribe` endpoint JSONRPC wrapper
+
+use crate::rpc;
+use serde::{Deserialize, Serialize};
+
+/// Unsubscribe from events matching a query on the websocket
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Request {
+    query: String,
+}
+
+impl Request {
+    /// Stop streaming the events matching the given query, which must be
+    /// identical to the one used to subscribe
+    pub fn new(query: String) -> Self {
+        Self { query }
+    }
+}
+
+impl rpc::Request for Request {
+    type Response = Response;
+
+    fn method(&self) -> rpc::Method {
+        rpc::Method::Unsubscribe
+    }
+}
+
+/// Unsubscribe responses
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Response {}
+
+impl rpc::Response for Response {}
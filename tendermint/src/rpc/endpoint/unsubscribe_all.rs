@@ -0,0 +1,22 @@
+//! `/unsubscribe_all` endpoint JSONRPC wrapper
+
+use crate::rpc;
+use serde::{Deserialize, Serialize};
+
+/// Unsubscribe from all events on the websocket
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Request;
+
+impl rpc::Request for Request {
+    type Response = Response;
+
+    fn method(&self) -> rpc::Method {
+        rpc::Method::UnsubscribeAll
+    }
+}
+
+/// Unsubscribe all responses
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Response {}
+
+impl rpc::Response for Response {}
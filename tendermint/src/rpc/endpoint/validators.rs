@@ -1,18 +1,69 @@
 //! `/validators` endpoint JSONRPC wrapper
 
-use crate::{block, rpc, validator};
+use crate::{block, rpc, serializers, validator};
 use serde::{Deserialize, Serialize};
 
+/// Number of validators per page, when unspecified
+pub const DEFAULT_PER_PAGE: u8 = 30;
+
+/// Maximum number of validators per page
+pub const MAX_PER_PAGE: u8 = 100;
+
 /// List validators for a specific block
+///
+/// Validators are paginated: see [`Client::validator_set`] to fetch all of them.
+///
+/// [`Client::validator_set`]: ../../struct.Client.html#method.validator_set
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Request {
     height: block::Height,
+
+    #[serde(default = "default_page", with = "serializers::from_str")]
+    page: u32,
+
+    #[serde(default = "default_per_page", with = "serializers::from_str")]
+    per_page: u8,
+}
+
+fn default_page() -> u32 {
+    1
+}
+
+fn default_per_page() -> u8 {
+    DEFAULT_PER_PAGE
 }
 
 impl Request {
-    /// List validators for a specific block
+    /// List the first page of validators for a specific block
     pub fn new(height: block::Height) -> Self {
-        Self { height }
+        Self::paginated(height, 1, DEFAULT_PER_PAGE)
+    }
+
+    /// List the given page of validators for a specific block, starting at
+    /// page 1.
+    ///
+    /// Pages hold at most `MAX_PER_PAGE` validators.
+    pub fn paginated(height: block::Height, page: u32, per_page: u8) -> Self {
+        Self {
+            height,
+            page,
+            per_page,
+        }
+    }
+
+    /// Height of the block to list validators for
+    pub fn height(&self) -> block::Height {
+        self.height
+    }
+
+    /// Page of validators to list
+    pub fn page(&self) -> u32 {
+        self.page
+    }
+
+    /// Number of validators per page
+    pub fn per_page(&self) -> u8 {
+        self.per_page
     }
 }
 
@@ -32,6 +83,16 @@ pub struct Response {
 
     /// Validator list
     pub validators: Vec<validator::Info>,
+
+    /// Number of validators in this page
+    #[serde(default, with = "serializers::from_str")]
+    pub count: u32,
+
+    /// Total number of validators, across all pages.
+    ///
+    /// Zero for nodes older than v0.33, which don't paginate validators.
+    #[serde(default, with = "serializers::from_str")]
+    pub total: u32,
 }
 
 impl rpc::Response for Response {}
@@ -1,24 +1,27 @@
-//! Tendermint Websocket event listener client
+//! Tendermint Websocket events, as streamed by a [`SubscriptionClient`]
+//!
+//! [`SubscriptionClient`]: ../subscription/struct.SubscriptionClient.html
 
 use crate::{
-    block::Block,
-    net,
+    account,
+    block::{self, Block},
+    consensus, net,
+    rpc::query::{EventType, Query},
     rpc::response,
     rpc::response::Wrapper,
-    rpc::Request,
-    rpc::{endpoint::subscribe, Error as RPCError},
+    rpc::subscription::{Backoff, Subscription, SubscriptionClient},
+    rpc::{Error as RPCError, TlsConfig, Url},
+    serializers, validator,
+    vote::Vote,
 };
-use async_tungstenite::{tokio::connect_async, tokio::TokioAdapter, tungstenite::Message};
-use futures::prelude::*;
+use futures::stream::{SelectAll, StreamExt};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::error::Error as stdError;
 
-use crate::rpc::error::Code;
-use tokio::net::TcpStream;
-
-/// There are only two valid queries to the websocket. A query that subscribes to all transactions
-/// and a query that susbscribes to all blocks.
+/// Shorthands for the queries subscribing to all transactions and to all blocks.
+///
+/// Other queries can be built with [`Query`].
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum EventSubscription {
     /// Subscribe to all transactions
@@ -37,77 +40,67 @@ impl EventSubscription {
     }
 }
 
+impl From<EventSubscription> for Query {
+    fn from(subscription: EventSubscription) -> Self {
+        match subscription {
+            EventSubscription::TransactionSubscription => Query::from(EventType::Tx),
+            EventSubscription::BlockSubscription => Query::from(EventType::NewBlock),
+        }
+    }
+}
+
 /// Event Listener over websocket.
 /// See: <https://docs.tendermint.com/master/rpc/#/Websocket/subscribe>
+///
+/// This is now a thin wrapper over a [`SubscriptionClient`], which streams the
+/// events of each subscription separately.
+///
+/// [`SubscriptionClient`]: ../subscription/struct.SubscriptionClient.html
+#[deprecated(
+    since = "0.14.0",
+    note = "use `SubscriptionClient`, which streams the events of each subscription separately"
+)]
 pub struct EventListener {
-    socket: async_tungstenite::WebSocketStream<TokioAdapter<TcpStream>>,
+    client: SubscriptionClient,
+    events: SelectAll<Subscription>,
 }
 
+#[allow(deprecated)]
 impl EventListener {
     /// Constructor for event listener
     pub async fn connect(address: net::Address) -> Result<EventListener, RPCError> {
-        let (host, port) = match address {
-            net::Address::Tcp { host, port, .. } => (host, port),
-            other => {
-                return Err(RPCError::invalid_params(&format!(
-                    "invalid RPC address: {:?}",
-                    other
-                )));
-            }
-        };
-        //TODO This doesn't have any way to handle a connection over TLS
-        let (ws_stream, _unused_tls_stream) =
-            connect_async(&format!("ws://{}:{}/websocket", host, port)).await?;
-        Ok(EventListener { socket: ws_stream })
+        Ok(Self::new(SubscriptionClient::connect(address).await?))
+    }
+
+    /// Connect to the websocket endpoint at the given `ws://` or `wss://` URL,
+    /// authenticating the server with the given TLS settings over `wss://`
+    pub async fn connect_url(url: &Url, tls: TlsConfig) -> Result<EventListener, RPCError> {
+        Ok(Self::new(
+            SubscriptionClient::connect_url(url, tls, Backoff::default()).await?,
+        ))
+    }
+
+    fn new(client: SubscriptionClient) -> Self {
+        Self {
+            client,
+            events: SelectAll::new(),
+        }
     }
 
     /// Subscribe to event query stream over the websocket
-    pub async fn subscribe(&mut self, query: EventSubscription) -> Result<(), Box<dyn stdError>> {
-        self.socket
-            .send(Message::text(
-                subscribe::Request::new(query.as_str().to_owned()).into_json(),
-            ))
-            .await?;
-        // TODO(ismail): this works if subscriptions are fired sequentially and no event or
-        // ping message gets in the way:
-        // Wait for an empty response on subscribe
-        let msg = self
-            .socket
-            .next()
-            .await
-            .ok_or_else(|| RPCError::websocket_error("web socket closed"))??;
-        serde_json::from_str::<Wrapper<subscribe::Response>>(&msg.to_string())?.into_result()?;
+    pub async fn subscribe(&mut self, query: impl Into<Query>) -> Result<(), Box<dyn stdError>> {
+        let subscription = self.client.subscribe(query).await?;
+        self.events.push(subscription);
 
         Ok(())
     }
 
-    /// Get the next event from the websocket
+    /// Get the next event from the websocket, of any of the subscriptions
     pub async fn get_event(&mut self) -> Result<Option<ResultEvent>, RPCError> {
-        let msg = self
-            .socket
-            .next()
-            .await
-            .ok_or_else(|| RPCError::websocket_error("web socket closed"))??;
-
-        if let Ok(result_event) = serde_json::from_str::<WrappedResultEvent>(&msg.to_string()) {
-            // if we get an rpc error here, we will bubble it up:
-            return Ok(Some(result_event.into_result()?));
+        match self.events.next().await {
+            Some(event) => event.map(Some),
+            None => Err(RPCError::websocket_error("no active subscription")),
         }
-        dbg!("We did not receive a valid JSONRPC wrapped ResultEvent!");
-        if serde_json::from_str::<String>(&msg.to_string()).is_ok() {
-            // FIXME(ismail): Until this is a proper websocket client
-            // (or the endpoint moved to grpc in tendermint), we accept whatever was read here
-            // dbg! it out and return None below.
-            dbg!("Instead of JSONRPC wrapped ResultEvent, we got:");
-            dbg!(&msg.to_string());
-            return Ok(None);
-        }
-        dbg!("received neither event nor generic string message:");
-        dbg!(&msg.to_string());
-        Err(RPCError::new(
-            Code::Other(-1),
-            Some("received neither event nor generic string message".to_string()),
-        ))
     }
 }
 
@@ -124,10 +117,45 @@ pub enum TMEventData {
     #[serde(alias = "tendermint/event/NewBlock")]
     EventDataNewBlock(EventDataNewBlock),
 
+    /// EventDataNewBlockHeader is returned upon subscribing to
+    /// "tm.event='NewBlockHeader'"
+    #[serde(alias = "tendermint/event/NewBlockHeader")]
+    EventDataNewBlockHeader(EventDataNewBlockHeader),
+
     /// EventDataTx is returned upon subscribing to "tm.event='Tx'"
     #[serde(alias = "tendermint/event/Tx")]
     EventDataTx(EventDataTx),
 
+    /// EventDataVote is returned upon subscribing to "tm.event='Vote'"
+    #[serde(alias = "tendermint/event/Vote")]
+    EventDataVote(EventDataVote),
+
+    /// EventDataNewRound is returned upon subscribing to "tm.event='NewRound'"
+    #[serde(alias = "tendermint/event/NewRound")]
+    EventDataNewRound(EventDataNewRound),
+
+    /// EventDataCompleteProposal is returned upon subscribing to
+    /// "tm.event='CompleteProposal'", with the ID of the proposed block
+    #[serde(alias = "tendermint/event/CompleteProposal")]
+    EventDataCompleteProposal(EventDataRoundState),
+
+    /// EventDataRoundState is returned upon subscribing to any of
+    /// "tm.event='NewRoundStep'", "tm.event='Polka'", "tm.event='Lock'",
+    /// "tm.event='Unlock'", "tm.event='Relock'", "tm.event='ValidBlock'",
+    /// "tm.event='TimeoutPropose'" or "tm.event='TimeoutWait'".
+    ///
+    /// As these events share the same data, use [`ResultEvent::event_type`]
+    /// to tell them apart.
+    ///
+    /// [`ResultEvent::event_type`]: struct.ResultEvent.html#method.event_type
+    #[serde(alias = "tendermint/event/RoundState")]
+    EventDataRoundState(EventDataRoundState),
+
+    /// EventDataValidatorSetUpdates is returned upon subscribing to
+    /// "tm.event='ValidatorSetUpdates'"
+    #[serde(alias = "tendermint/event/ValidatorSetUpdates")]
+    EventDataValidatorSetUpdates(EventDataValidatorSetUpdates),
+
     /// Generic event containing json data
     GenericJSONEvent(
         /// generic event json data
@@ -147,6 +175,13 @@ pub struct ResultEvent {
 }
 impl response::Response for ResultEvent {}
 
+impl ResultEvent {
+    /// Type of this event, as found in its `tm.event` attribute
+    pub fn event_type(&self) -> Option<EventType> {
+        self.events.as_ref()?.get("tm.event")?.first()?.parse().ok()
+    }
+}
+
 /// JSONRPC wrapped ResultEvent
 pub type WrappedResultEvent = Wrapper<ResultEvent>;
 
@@ -210,5 +245,99 @@ pub struct ResultBeginBlock {
 ///End Block Events
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ResultEndBlock {
-    pub validator_updates: Option<Vec<Option<serde_json::Value>>>,
+    pub validator_updates: Option<Vec<validator::Update>>,
+}
+
+/// Block header value
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EventDataNewBlockHeader {
+    pub header: block::Header,
+    pub result_begin_block: Option<ResultBeginBlock>,
+    pub result_end_block: Option<ResultEndBlock>,
+}
+
+/// Vote value
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EventDataVote {
+    /// Vote received by the node
+    #[serde(rename = "Vote")]
+    pub vote: Vote,
+}
+
+/// New round value
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EventDataNewRound {
+    /// Height of the round
+    pub height: block::Height,
+
+    /// Consensus round
+    #[serde(with = "serializers::from_str")]
+    pub round: i64,
+
+    /// Step of the round
+    #[serde(with = "round_step")]
+    pub step: consensus::RoundStep,
+
+    /// Proposer of the round
+    pub proposer: ProposerInfo,
+}
+
+/// Round state value
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EventDataRoundState {
+    /// Height of the round
+    pub height: block::Height,
+
+    /// Consensus round
+    #[serde(with = "serializers::from_str")]
+    pub round: i64,
+
+    /// Step of the round
+    #[serde(with = "round_step")]
+    pub step: consensus::RoundStep,
+
+    /// Block ID of the complete proposal, if any
+    #[serde(
+        default,
+        deserialize_with = "serializers::parse_non_empty_block_id",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub block_id: Option<block::Id>,
+}
+
+/// Proposer of a round
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProposerInfo {
+    /// Validator address
+    pub address: account::Id,
+
+    /// Index of the validator in the validator set
+    #[serde(with = "serializers::from_str")]
+    pub index: u64,
+}
+
+/// Validator set updates value
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EventDataValidatorSetUpdates {
+    /// Validators whose voting power changed, including removed validators
+    /// with a voting power of zero
+    pub validator_updates: Vec<validator::Info>,
+}
+
+/// (De)serialize round steps by name, eg. `RoundStepPropose`, as found in
+/// round state events
+mod round_step {
+    use crate::consensus::RoundStep;
+    use serde::{de::Error as _, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(step: &RoundStep, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(step.name())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<RoundStep, D::Error> {
+        let name = String::deserialize(deserializer)?;
+
+        RoundStep::from_name(&name)
+            .ok_or_else(|| D::Error::custom(format!("unknown round step: {}", name)))
+    }
 }
@@ -27,6 +27,9 @@ pub enum Method {
     /// Get blockchain info
     Blockchain,
 
+    /// Broadcast evidence of malfeasance by a validator
+    BroadcastEvidence,
+
     /// Broadcast transaction asynchronously
     BroadcastTxAsync,
 
@@ -36,9 +39,21 @@ pub enum Method {
     /// Broadcast transaction commit
     BroadcastTxCommit,
 
+    /// Check a transaction without adding it to the mempool
+    CheckTx,
+
     /// Get commit info for a block
     Commit,
 
+    /// Get consensus parameters
+    ConsensusParams,
+
+    /// Get a summary of the consensus state
+    ConsensusState,
+
+    /// Get the complete consensus state, including that of peers
+    DumpConsensusState,
+
     /// Get genesis file
     Genesis,
 
@@ -48,14 +63,32 @@ pub enum Method {
     /// Get network info
     NetInfo,
 
+    /// Get the number of unconfirmed transactions
+    NumUnconfirmedTxs,
+
     /// Get node status
     Status,
 
+    /// Find transaction by hash
+    Tx,
+
+    /// Search for transactions with their results
+    TxSearch,
+
+    /// Get unconfirmed transactions
+    UnconfirmedTxs,
+
     /// Get validator info for a block
     Validators,
 
     /// Subscribe to events over the websocket
     Subscribe,
+
+    /// Unsubscribe from events over the websocket
+    Unsubscribe,
+
+    /// Unsubscribe from all events over the websocket
+    UnsubscribeAll,
 }
 
 impl Method {
@@ -67,16 +100,42 @@ impl Method {
             Method::Block => "block",
             Method::BlockResults => "block_results",
             Method::Blockchain => "blockchain",
+            Method::BroadcastEvidence => "broadcast_evidence",
             Method::BroadcastTxAsync => "broadcast_tx_async",
             Method::BroadcastTxSync => "broadcast_tx_sync",
             Method::BroadcastTxCommit => "broadcast_tx_commit",
+            Method::CheckTx => "check_tx",
             Method::Commit => "commit",
+            Method::ConsensusParams => "consensus_params",
+            Method::ConsensusState => "consensus_state",
+            Method::DumpConsensusState => "dump_consensus_state",
             Method::Genesis => "genesis",
             Method::Health => "health",
             Method::NetInfo => "net_info",
+            Method::NumUnconfirmedTxs => "num_unconfirmed_txs",
             Method::Status => "status",
+            Method::Tx => "tx",
+            Method::TxSearch => "tx_search",
+            Method::UnconfirmedTxs => "unconfirmed_txs",
             Method::Validators => "validators",
             Method::Subscribe => "subscribe",
+            Method::Unsubscribe => "unsubscribe",
+            Method::UnsubscribeAll => "unsubscribe_all",
+        }
+    }
+
+    /// Can requests with this method be sent again without further effect,
+    /// ie. are they safe to retry?
+    ///
+    /// Broadcasting a transaction or evidence is not, as it may have reached
+    /// the node even though its response didn't reach us.
+    pub fn is_idempotent(self) -> bool {
+        match self {
+            Method::BroadcastEvidence
+            | Method::BroadcastTxAsync
+            | Method::BroadcastTxSync
+            | Method::BroadcastTxCommit => false,
+            _ => true,
         }
     }
 }
@@ -91,16 +150,27 @@ impl FromStr for Method {
             "block" => Method::Block,
             "block_results" => Method::BlockResults,
             "blockchain" => Method::Blockchain,
+            "broadcast_evidence" => Method::BroadcastEvidence,
             "broadcast_tx_async" => Method::BroadcastTxAsync,
             "broadcast_tx_sync" => Method::BroadcastTxSync,
             "broadcast_tx_commit" => Method::BroadcastTxCommit,
+            "check_tx" => Method::CheckTx,
             "commit" => Method::Commit,
+            "consensus_params" => Method::ConsensusParams,
+            "consensus_state" => Method::ConsensusState,
+            "dump_consensus_state" => Method::DumpConsensusState,
             "genesis" => Method::Genesis,
             "health" => Method::Health,
             "net_info" => Method::NetInfo,
+            "num_unconfirmed_txs" => Method::NumUnconfirmedTxs,
             "status" => Method::Status,
+            "tx" => Method::Tx,
+            "tx_search" => Method::TxSearch,
+            "unconfirmed_txs" => Method::UnconfirmedTxs,
             "validators" => Method::Validators,
             "subscribe" => Method::Subscribe,
+            "unsubscribe" => Method::Unsubscribe,
+            "unsubscribe_all" => Method::UnsubscribeAll,
             other => return Err(Error::method_not_found(other)),
         })
     }
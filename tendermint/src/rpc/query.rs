@@ -0,0 +1,771 @@
+//! Queries on events, in the language of Tendermint's pubsub
+//!
+//! Queries are used to subscribe to events over the websocket, and to search
+//! for transactions. They consist of conditions on the attributes of events,
+//! joined by `AND`:
+//!
+//! ```
+//! use tendermint::rpc::query::{EventType, Query};
+//!
+//! # fn main() -> Result<(), tendermint::Error> {
+//! let query = Query::from(EventType::Tx)
+//!     .and_eq("transfer.recipient", "cosmos1...")?
+//!     .and_gte("tx.height", 100u64)?;
+//!
+//! assert_eq!(
+//!     query.to_string(),
+//!     "tm.event = 'Tx' AND transfer.recipient = 'cosmos1...' AND tx.height >= 100"
+//! );
+//! assert_eq!(query.to_string().parse::<Query>()?, query);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Queries can't contain quotes in their strings, nor non-finite numbers, so
+//! building a query with such operands fails.
+//!
+//! <https://github.com/tendermint/tendermint/blob/v0.33.5/libs/pubsub/query/query.peg>
+
+use crate::{
+    block,
+    error::{Error, Kind},
+    Time,
+};
+use anomaly::{fail, format_err};
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// Query on events: a non-empty list of conditions, all of which must hold
+#[derive(Clone, Debug, PartialEq)]
+pub struct Query {
+    conditions: Vec<Condition>,
+}
+
+impl Query {
+    /// Query matching events with `key` equal to `value`
+    pub fn eq(key: impl Into<String>, value: impl Into<Operand>) -> Result<Self, Error> {
+        Self::new(key, Operation::Eq(value.into()))
+    }
+
+    /// Query matching events with `key` less than `value`
+    pub fn lt(key: impl Into<String>, value: impl Into<Operand>) -> Result<Self, Error> {
+        Self::new(key, Operation::Lt(value.into()))
+    }
+
+    /// Query matching events with `key` less than or equal to `value`
+    pub fn lte(key: impl Into<String>, value: impl Into<Operand>) -> Result<Self, Error> {
+        Self::new(key, Operation::Lte(value.into()))
+    }
+
+    /// Query matching events with `key` greater than `value`
+    pub fn gt(key: impl Into<String>, value: impl Into<Operand>) -> Result<Self, Error> {
+        Self::new(key, Operation::Gt(value.into()))
+    }
+
+    /// Query matching events with `key` greater than or equal to `value`
+    pub fn gte(key: impl Into<String>, value: impl Into<Operand>) -> Result<Self, Error> {
+        Self::new(key, Operation::Gte(value.into()))
+    }
+
+    /// Query matching events with `key` containing the string `value`
+    pub fn contains(key: impl Into<String>, value: impl Into<String>) -> Result<Self, Error> {
+        Self::new(key, Operation::Contains(value.into()))
+    }
+
+    /// Query matching events with an attribute `key`
+    pub fn exists(key: impl Into<String>) -> Result<Self, Error> {
+        Self::new(key, Operation::Exists)
+    }
+
+    fn new(key: impl Into<String>, operation: Operation) -> Result<Self, Error> {
+        Ok(Self {
+            conditions: vec![Condition::new(key, operation)?],
+        })
+    }
+
+    /// Add the condition that `key` is equal to `value`
+    pub fn and_eq(self, key: impl Into<String>, value: impl Into<Operand>) -> Result<Self, Error> {
+        Ok(self.and(Condition::new(key, Operation::Eq(value.into()))?))
+    }
+
+    /// Add the condition that `key` is less than `value`
+    pub fn and_lt(self, key: impl Into<String>, value: impl Into<Operand>) -> Result<Self, Error> {
+        Ok(self.and(Condition::new(key, Operation::Lt(value.into()))?))
+    }
+
+    /// Add the condition that `key` is less than or equal to `value`
+    pub fn and_lte(self, key: impl Into<String>, value: impl Into<Operand>) -> Result<Self, Error> {
+        Ok(self.and(Condition::new(key, Operation::Lte(value.into()))?))
+    }
+
+    /// Add the condition that `key` is greater than `value`
+    pub fn and_gt(self, key: impl Into<String>, value: impl Into<Operand>) -> Result<Self, Error> {
+        Ok(self.and(Condition::new(key, Operation::Gt(value.into()))?))
+    }
+
+    /// Add the condition that `key` is greater than or equal to `value`
+    pub fn and_gte(self, key: impl Into<String>, value: impl Into<Operand>) -> Result<Self, Error> {
+        Ok(self.and(Condition::new(key, Operation::Gte(value.into()))?))
+    }
+
+    /// Add the condition that `key` contains the string `value`
+    pub fn and_contains(
+        self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Result<Self, Error> {
+        Ok(self.and(Condition::new(key, Operation::Contains(value.into()))?))
+    }
+
+    /// Add the condition that there is an attribute `key`
+    pub fn and_exists(self, key: impl Into<String>) -> Result<Self, Error> {
+        Ok(self.and(Condition::new(key, Operation::Exists)?))
+    }
+
+    /// Add a condition to the query
+    pub fn and(mut self, condition: Condition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    /// Get the conditions of the query
+    pub fn conditions(&self) -> &[Condition] {
+        &self.conditions
+    }
+}
+
+impl Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, condition) in self.conditions.iter().enumerate() {
+            if i > 0 {
+                write!(f, " AND ")?;
+            }
+
+            write!(f, "{}", condition)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Query {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let mut parser = Parser { input: s.trim() };
+        let mut conditions = vec![parser.condition()?];
+
+        while !parser.input.is_empty() {
+            parser.keyword("AND")?;
+            conditions.push(parser.condition()?);
+        }
+
+        Ok(Self { conditions })
+    }
+}
+
+impl From<EventType> for Query {
+    fn from(event_type: EventType) -> Self {
+        Self {
+            conditions: vec![Condition {
+                key: "tm.event".to_owned(),
+                operation: Operation::Eq(event_type.as_str().into()),
+            }],
+        }
+    }
+}
+
+/// Condition on an attribute of events
+#[derive(Clone, Debug, PartialEq)]
+pub struct Condition {
+    /// Attribute key, eg. `tx.height` or `transfer.recipient`
+    pub key: String,
+
+    /// Operation applied to the attribute
+    pub operation: Operation,
+}
+
+impl Condition {
+    /// Create a new condition on the attribute `key`, checking that both the
+    /// key and the operand can be written in a query
+    pub fn new(key: impl Into<String>, operation: Operation) -> Result<Self, Error> {
+        let key = key.into();
+
+        if key.is_empty() || key.contains(|c: char| c.is_whitespace() || KEY_DELIMITERS.contains(c))
+        {
+            fail!(Kind::Parse, "invalid key: {}", key);
+        }
+
+        match &operation {
+            Operation::Eq(operand)
+            | Operation::Lt(operand)
+            | Operation::Lte(operand)
+            | Operation::Gt(operand)
+            | Operation::Gte(operand) => operand.validate()?,
+            Operation::Contains(value) => validate_string(value)?,
+            Operation::Exists => (),
+        }
+
+        Ok(Self { key, operation })
+    }
+}
+
+impl Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.operation {
+            Operation::Eq(operand) => write!(f, "{} = {}", self.key, operand),
+            Operation::Lt(operand) => write!(f, "{} < {}", self.key, operand),
+            Operation::Lte(operand) => write!(f, "{} <= {}", self.key, operand),
+            Operation::Gt(operand) => write!(f, "{} > {}", self.key, operand),
+            Operation::Gte(operand) => write!(f, "{} >= {}", self.key, operand),
+            Operation::Contains(value) => write!(f, "{} CONTAINS '{}'", self.key, value),
+            Operation::Exists => write!(f, "{} EXISTS", self.key),
+        }
+    }
+}
+
+/// Operation applied to an attribute
+#[derive(Clone, Debug, PartialEq)]
+pub enum Operation {
+    /// Equal to the operand
+    Eq(Operand),
+
+    /// Less than the operand (numbers, dates and times only)
+    Lt(Operand),
+
+    /// Less than or equal to the operand (numbers, dates and times only)
+    Lte(Operand),
+
+    /// Greater than the operand (numbers, dates and times only)
+    Gt(Operand),
+
+    /// Greater than or equal to the operand (numbers, dates and times only)
+    Gte(Operand),
+
+    /// Contains the string
+    Contains(String),
+
+    /// Attribute is present
+    Exists,
+}
+
+/// Typed operand of a condition
+#[derive(Clone, Debug, PartialEq)]
+pub enum Operand {
+    /// String, which must not contain quotes
+    String(String),
+
+    /// Unsigned integer, eg. a height
+    Unsigned(u64),
+
+    /// Floating point number
+    Float(f64),
+
+    /// Date
+    Date(NaiveDate),
+
+    /// Time
+    DateTime(DateTime<Utc>),
+}
+
+impl Operand {
+    /// Check that the operand can be written in a query: strings can't contain
+    /// quotes, and floats must be finite
+    fn validate(&self) -> Result<(), Error> {
+        match self {
+            Operand::String(s) => validate_string(s),
+            Operand::Float(n) if !n.is_finite() => {
+                fail!(Kind::OutOfRange, "non-finite operand: {}", n)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::String(s) => write!(f, "'{}'", s),
+            Operand::Unsigned(n) => write!(f, "{}", n),
+            Operand::Float(n) if n.to_string().contains('.') => write!(f, "{}", n),
+            Operand::Float(n) => write!(f, "{}.0", n),
+            Operand::Date(date) => write!(f, "DATE {}", date.format("%Y-%m-%d")),
+            // Go parses fractional seconds in RFC 3339 times, so that
+            // sub-second times are written as is
+            Operand::DateTime(time) => write!(
+                f,
+                "TIME {}",
+                time.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+            ),
+        }
+    }
+}
+
+impl From<&str> for Operand {
+    fn from(s: &str) -> Self {
+        Operand::String(s.to_owned())
+    }
+}
+
+impl From<String> for Operand {
+    fn from(s: String) -> Self {
+        Operand::String(s)
+    }
+}
+
+impl From<u64> for Operand {
+    fn from(n: u64) -> Self {
+        Operand::Unsigned(n)
+    }
+}
+
+impl From<u32> for Operand {
+    fn from(n: u32) -> Self {
+        Operand::Unsigned(n.into())
+    }
+}
+
+impl From<f64> for Operand {
+    fn from(n: f64) -> Self {
+        Operand::Float(n)
+    }
+}
+
+impl From<block::Height> for Operand {
+    fn from(height: block::Height) -> Self {
+        Operand::Unsigned(height.value())
+    }
+}
+
+impl From<NaiveDate> for Operand {
+    fn from(date: NaiveDate) -> Self {
+        Operand::Date(date)
+    }
+}
+
+impl From<DateTime<Utc>> for Operand {
+    fn from(time: DateTime<Utc>) -> Self {
+        Operand::DateTime(time)
+    }
+}
+
+impl From<Time> for Operand {
+    fn from(time: Time) -> Self {
+        Operand::DateTime(time.into())
+    }
+}
+
+/// Types of events published by Tendermint, as found in the `tm.event`
+/// attribute
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum EventType {
+    /// New block
+    NewBlock,
+
+    /// New block header
+    NewBlockHeader,
+
+    /// Transaction
+    Tx,
+
+    /// Validator set updates
+    ValidatorSetUpdates,
+
+    /// New consensus round
+    NewRound,
+
+    /// New step of a consensus round
+    NewRoundStep,
+
+    /// Complete proposal received for the current round
+    CompleteProposal,
+
+    /// Vote received
+    Vote,
+
+    /// More than 2/3 of prevotes for a block or nil
+    Polka,
+
+    /// Validator locked on a block
+    Lock,
+
+    /// Validator unlocked from a block
+    Unlock,
+
+    /// Validator locked again on its locked block
+    Relock,
+
+    /// Block seen with more than 2/3 of prevotes
+    ValidBlock,
+
+    /// Timeout waiting for a proposal
+    TimeoutPropose,
+
+    /// Timeout waiting for more prevotes or precommits
+    TimeoutWait,
+}
+
+impl EventType {
+    /// Get the value of the `tm.event` attribute for this type of event
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EventType::NewBlock => "NewBlock",
+            EventType::NewBlockHeader => "NewBlockHeader",
+            EventType::Tx => "Tx",
+            EventType::ValidatorSetUpdates => "ValidatorSetUpdates",
+            EventType::NewRound => "NewRound",
+            EventType::NewRoundStep => "NewRoundStep",
+            EventType::CompleteProposal => "CompleteProposal",
+            EventType::Vote => "Vote",
+            EventType::Polka => "Polka",
+            EventType::Lock => "Lock",
+            EventType::Unlock => "Unlock",
+            EventType::Relock => "Relock",
+            EventType::ValidBlock => "ValidBlock",
+            EventType::TimeoutPropose => "TimeoutPropose",
+            EventType::TimeoutWait => "TimeoutWait",
+        }
+    }
+}
+
+impl Display for EventType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EventType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(match s {
+            "NewBlock" => EventType::NewBlock,
+            "NewBlockHeader" => EventType::NewBlockHeader,
+            "Tx" => EventType::Tx,
+            "ValidatorSetUpdates" => EventType::ValidatorSetUpdates,
+            "NewRound" => EventType::NewRound,
+            "NewRoundStep" => EventType::NewRoundStep,
+            "CompleteProposal" => EventType::CompleteProposal,
+            "Vote" => EventType::Vote,
+            "Polka" => EventType::Polka,
+            "Lock" => EventType::Lock,
+            "Unlock" => EventType::Unlock,
+            "Relock" => EventType::Relock,
+            "ValidBlock" => EventType::ValidBlock,
+            "TimeoutPropose" => EventType::TimeoutPropose,
+            "TimeoutWait" => EventType::TimeoutWait,
+            other => fail!(Kind::Parse, "unknown event type: {}", other),
+        })
+    }
+}
+
+/// Characters which end the key of a condition
+const KEY_DELIMITERS: &str = "\\()\"'=<>";
+
+/// Check that a string can be quoted in a query, which has no escape sequences
+fn validate_string(s: &str) -> Result<(), Error> {
+    if s.contains(|c| c == '\'' || c == '"') {
+        fail!(Kind::Parse, "query strings can't contain quotes: {}", s);
+    }
+
+    Ok(())
+}
+
+/// Parser for queries, consuming its input as it goes
+struct Parser<'a> {
+    input: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    /// Parse a condition, and the whitespace following it
+    fn condition(&mut self) -> Result<Condition, Error> {
+        let key = self.key()?;
+
+        let operation = if self.consume("<=") {
+            Operation::Lte(self.ordered_operand()?)
+        } else if self.consume(">=") {
+            Operation::Gte(self.ordered_operand()?)
+        } else if self.consume("<") {
+            Operation::Lt(self.ordered_operand()?)
+        } else if self.consume(">") {
+            Operation::Gt(self.ordered_operand()?)
+        } else if self.consume("=") {
+            Operation::Eq(self.operand()?)
+        } else if self.consume("CONTAINS") {
+            Operation::Contains(self.string()?)
+        } else if self.consume("EXISTS") {
+            Operation::Exists
+        } else {
+            fail!(Kind::Parse, "expected operator, got: {}", self.input);
+        };
+
+        self.skip_whitespace();
+        Condition::new(key, operation)
+    }
+
+    /// Parse the key of a condition
+    fn key(&mut self) -> Result<String, Error> {
+        let end = self
+            .input
+            .find(|c: char| c.is_whitespace() || KEY_DELIMITERS.contains(c))
+            .unwrap_or(self.input.len());
+
+        if end == 0 {
+            fail!(Kind::Parse, "expected key, got: {}", self.input);
+        }
+
+        let key = self.take(end).to_owned();
+        self.skip_whitespace();
+        Ok(key)
+    }
+
+    /// Parse the operand of a comparison, which can't be a string
+    fn ordered_operand(&mut self) -> Result<Operand, Error> {
+        match self.operand()? {
+            Operand::String(s) => fail!(Kind::Parse, "can't compare with string: '{}'", s),
+            operand => Ok(operand),
+        }
+    }
+
+    fn operand(&mut self) -> Result<Operand, Error> {
+        self.skip_whitespace();
+
+        if self.input.starts_with('\'') {
+            return Ok(Operand::String(self.string()?));
+        }
+
+        if self.consume("DATE ") {
+            let date = self.word();
+            return NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map(Operand::Date)
+                .map_err(|e| format_err!(Kind::Parse, "invalid date {}: {}", date, e).into());
+        }
+
+        if self.consume("TIME ") {
+            let time = self.word();
+            return DateTime::parse_from_rfc3339(time)
+                .map(|time| Operand::DateTime(time.with_timezone(&Utc)))
+                .map_err(|e| format_err!(Kind::Parse, "invalid time {}: {}", time, e).into());
+        }
+
+        let number = self.word();
+        let operand = if number.contains('.') {
+            number.parse().ok().map(Operand::Float)
+        } else {
+            number.parse().ok().map(Operand::Unsigned)
+        };
+
+        operand.ok_or_else(|| format_err!(Kind::Parse, "invalid operand: {}", number).into())
+    }
+
+    /// Parse a quoted string
+    fn string(&mut self) -> Result<String, Error> {
+        self.skip_whitespace();
+
+        if !self.consume("'") {
+            fail!(Kind::Parse, "expected quoted string, got: {}", self.input);
+        }
+
+        let end = self
+            .input
+            .find(|c| c == '\'' || c == '"')
+            .filter(|&end| self.input[end..].starts_with('\''))
+            .ok_or_else(|| format_err!(Kind::Parse, "unterminated string: {}", self.input))?;
+
+        let string = self.take(end).to_owned();
+        self.consume("'");
+        Ok(string)
+    }
+
+    /// Consume the given keyword, which must be followed by whitespace
+    fn keyword(&mut self, keyword: &str) -> Result<(), Error> {
+        if self.input.starts_with(keyword)
+            && self.input[keyword.len()..].starts_with(char::is_whitespace)
+        {
+            self.take(keyword.len());
+            self.skip_whitespace();
+            Ok(())
+        } else {
+            fail!(Kind::Parse, "expected {}, got: {}", keyword, self.input)
+        }
+    }
+
+    /// Consume the given prefix if the input starts with it
+    fn consume(&mut self, prefix: &str) -> bool {
+        if self.input.starts_with(prefix) {
+            self.input = &self.input[prefix.len()..];
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consume characters up to the next whitespace
+    fn word(&mut self) -> &'a str {
+        let end = self
+            .input
+            .find(char::is_whitespace)
+            .unwrap_or(self.input.len());
+
+        self.take(end)
+    }
+
+    fn take(&mut self, len: usize) -> &'a str {
+        let (taken, rest) = self.input.split_at(len);
+        self.input = rest;
+        taken
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.input = self.input.trim_start();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn render_event_types() {
+        assert_eq!(Query::from(EventType::Tx).to_string(), "tm.event = 'Tx'");
+        assert_eq!(
+            Query::from(EventType::NewBlock).to_string(),
+            "tm.event = 'NewBlock'"
+        );
+    }
+
+    #[test]
+    fn parse_event_types() {
+        assert_eq!("Polka".parse::<EventType>().unwrap(), EventType::Polka);
+        assert_eq!(
+            EventType::TimeoutPropose
+                .to_string()
+                .parse::<EventType>()
+                .unwrap(),
+            EventType::TimeoutPropose
+        );
+        assert!("polka".parse::<EventType>().is_err());
+    }
+
+    #[test]
+    fn render_operations() -> Result<(), Error> {
+        let query = Query::eq("transfer.recipient", "cosmos1abc")?
+            .and_gt("tx.height", block::Height::from(5u64))?
+            .and_lte("account.balance", 3.5)?
+            .and_lt("tx.time", Utc.ymd(2020, 6, 1).and_hms(12, 30, 0))?
+            .and_gte("tx.date", NaiveDate::from_ymd(2020, 6, 1))?
+            .and_contains("message.action", "send")?
+            .and_exists("message.sender")?;
+
+        assert_eq!(
+            query.to_string(),
+            "transfer.recipient = 'cosmos1abc' AND tx.height > 5 AND account.balance <= 3.5 \
+             AND tx.time < TIME 2020-06-01T12:30:00Z AND tx.date >= DATE 2020-06-01 \
+             AND message.action CONTAINS 'send' AND message.sender EXISTS"
+        );
+
+        assert_eq!(query.to_string().parse::<Query>()?, query);
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_sub_second_times() -> Result<(), Error> {
+        let time = Utc.ymd(2020, 6, 1).and_hms_nano(12, 30, 0, 123_456_789);
+        let query = Query::from(EventType::NewBlock).and_gt("block.time", time)?;
+
+        assert_eq!(
+            query.to_string(),
+            "tm.event = 'NewBlock' AND block.time > TIME 2020-06-01T12:30:00.123456789Z"
+        );
+        assert_eq!(query.to_string().parse::<Query>()?, query);
+
+        let time = Utc.ymd(2020, 6, 1).and_hms_milli(12, 30, 0, 500);
+        let query = Query::lte("block.time", time)?;
+        assert_eq!(
+            query.to_string(),
+            "block.time <= TIME 2020-06-01T12:30:00.500Z"
+        );
+        assert_eq!(query.to_string().parse::<Query>()?, query);
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_queries() {
+        assert_eq!(
+            "tm.event='Tx'".parse::<Query>().unwrap(),
+            Query::from(EventType::Tx)
+        );
+
+        assert_eq!(
+            "tm.event = 'NewBlock'  AND   block.height>=10"
+                .parse::<Query>()
+                .unwrap(),
+            Query::from(EventType::NewBlock)
+                .and_gte("block.height", 10u64)
+                .unwrap()
+        );
+
+        assert_eq!(
+            "account.balance = 2.0".parse::<Query>().unwrap(),
+            Query::eq("account.balance", 2.0).unwrap()
+        );
+
+        assert_eq!(
+            "tx.time > TIME 2013-05-03T14:45:00+02:00"
+                .parse::<Query>()
+                .unwrap(),
+            Query::gt("tx.time", Utc.ymd(2013, 5, 3).and_hms(12, 45, 0)).unwrap()
+        );
+
+        assert_eq!(
+            "message.memo CONTAINS 'AND = EXISTS'"
+                .parse::<Query>()
+                .unwrap(),
+            Query::contains("message.memo", "AND = EXISTS").unwrap()
+        );
+    }
+
+    #[test]
+    fn reject_invalid_queries() {
+        for query in &[
+            "",
+            "tm.event",
+            "tm.event = ",
+            "tm.event = 'Tx",
+            "tm.event = Tx",
+            "tm.event = 'Tx' tx.height = 5",
+            "tm.event = 'Tx' ANDtx.height = 5",
+            "tx.height > 'five'",
+            "tx.height = -5",
+            "tx.date = DATE 2020-13-01",
+            "tx.time = TIME yesterday",
+            "message.action CONTAINS send",
+            "tm.event EXISTS AND",
+        ] {
+            assert!(query.parse::<Query>().is_err(), "parsed: {}", query);
+        }
+    }
+
+    #[test]
+    fn reject_invalid_operands() {
+        assert!(Query::eq("message.memo", "it's").is_err());
+        assert!(Query::eq("message.memo", "say \"hi\"").is_err());
+        assert!(Query::contains("message.memo", "'").is_err());
+        assert!(Query::from(EventType::Tx)
+            .and_eq("message.memo", "' OR tm.event = 'NewBlock")
+            .is_err());
+
+        assert!(Query::eq("account.balance", std::f64::NAN).is_err());
+        assert!(Query::gt("account.balance", std::f64::INFINITY).is_err());
+        assert!(Query::lt("account.balance", std::f64::NEG_INFINITY).is_err());
+
+        assert!(Query::exists("").is_err());
+        assert!(Query::exists("message sender").is_err());
+        assert!(Query::eq("tm.event='Tx'", "Tx").is_err());
+    }
+}
@@ -20,7 +20,7 @@ pub trait Request: Debug + DeserializeOwned + Serialize + Sized {
 
 /// JSONRPC request wrapper (i.e. message envelope)
 #[derive(Debug, Deserialize, Serialize)]
-struct Wrapper<R> {
+pub struct Wrapper<R> {
     /// JSONRPC version
     jsonrpc: Version,
 
@@ -48,3 +48,30 @@ where
         }
     }
 }
+
+impl<R> Wrapper<R> {
+    /// Get JSONRPC version
+    pub fn version(&self) -> &Version {
+        &self.jsonrpc
+    }
+
+    /// Get JSONRPC ID
+    pub fn id(&self) -> &Id {
+        &self.id
+    }
+
+    /// Get request method
+    pub fn method(&self) -> Method {
+        self.method
+    }
+
+    /// Borrow the request parameters
+    pub fn params(&self) -> &R {
+        &self.params
+    }
+
+    /// Convert this wrapper into the request parameters
+    pub fn into_params(self) -> R {
+        self.params
+    }
+}
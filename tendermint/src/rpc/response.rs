@@ -36,17 +36,24 @@ pub struct Wrapper<R> {
     error: Option<Error>,
 }
 
-impl<R> Wrapper<R>
-where
-    R: Response,
-{
+impl<R> Wrapper<R> {
+    /// Create a new response wrapper for the request with the given ID,
+    /// containing either a result or an error.
+    pub fn new_with_id(id: Id, result: Option<R>, error: Option<Error>) -> Self {
+        Self {
+            jsonrpc: Version::current(),
+            id,
+            result,
+            error,
+        }
+    }
+
     /// Get JSONRPC version
     pub fn version(&self) -> &Version {
         &self.jsonrpc
     }
 
     /// Get JSONRPC ID
-    #[allow(dead_code)]
     pub fn id(&self) -> &Id {
         &self.id
     }
@@ -0,0 +1,655 @@
+//! Tendermint Websocket subscription client
+//!
+//! Runs any number of concurrent event subscriptions over a single websocket
+//! connection, each with its own [`Subscription`] stream of events. Events are
+//! routed to their subscription by the JSONRPC ID of the `subscribe` request
+//! (which Tendermint echoes with an `#event` suffix), or else by their query.
+//!
+//! When the connection drops, the client reconnects with exponential backoff
+//! and subscribes again to all the active queries. Each subscription then
+//! yields an error, as the events published in the meantime are lost.
+//!
+//! See: <https://docs.tendermint.com/master/rpc/#/Websocket/subscribe>
+
+use crate::{
+    net,
+    rpc::{
+        connection::{Peer, Stream, TlsConfig},
+        endpoint::{subscribe, unsubscribe, unsubscribe_all},
+        event_listener::ResultEvent,
+        query::Query,
+        request, response,
+        url::Url,
+        Error, Id, Request,
+    },
+};
+use async_tungstenite::{
+    tokio::{client_async, TokioAdapter},
+    tungstenite::Message,
+    WebSocketStream,
+};
+use futures::{
+    channel::{mpsc, oneshot},
+    prelude::*,
+    task::{Context, Poll},
+};
+use std::{
+    cmp,
+    collections::{BTreeMap, HashMap},
+    mem,
+    pin::Pin,
+    time::Duration,
+};
+
+/// Suffix Tendermint adds to the ID of a `subscribe` request for its events
+const EVENT_ID_SUFFIX: &str = "#event";
+
+type WebSocket = WebSocketStream<TokioAdapter<Stream>>;
+
+/// Delays between attempts to reconnect to the node, doubling from `initial`
+/// up to `max`
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Backoff {
+    /// Delay before the first attempt
+    pub initial: Duration,
+
+    /// Maximum delay between attempts
+    pub max: Duration,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_millis(100),
+            max: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Websocket client multiplexing event subscriptions over a single connection.
+///
+/// The connection is driven by a background task, which stops once the client
+/// and all its subscriptions have been dropped.
+#[derive(Clone)]
+pub struct SubscriptionClient {
+    commands: mpsc::UnboundedSender<Command>,
+}
+
+impl SubscriptionClient {
+    /// Connect to the websocket endpoint of the node at the given TCP or UNIX
+    /// socket address
+    pub async fn connect(address: net::Address) -> Result<Self, Error> {
+        Self::connect_with_backoff(address, Backoff::default()).await
+    }
+
+    /// Connect to the websocket endpoint of the node at the given TCP or UNIX
+    /// socket address, reconnecting with the given backoff when the connection
+    /// drops
+    pub async fn connect_with_backoff(
+        address: net::Address,
+        backoff: Backoff,
+    ) -> Result<Self, Error> {
+        Self::start(Peer::from_address(address), "/websocket", backoff).await
+    }
+
+    /// Connect to the websocket endpoint at the given `ws://` or `wss://` URL,
+    /// authenticating the server with the given TLS settings over `wss://`,
+    /// and reconnecting with the given backoff when the connection drops
+    pub async fn connect_url(url: &Url, tls: TlsConfig, backoff: Backoff) -> Result<Self, Error> {
+        if !url.scheme.is_websocket() {
+            return Err(Error::invalid_params(&format!(
+                "invalid websocket URL (expected ws:// or wss://): {}",
+                url
+            )));
+        }
+
+        Self::start(Peer::from_url(url, tls)?, &url.path, backoff).await
+    }
+
+    async fn start(peer: Peer, path: &str, backoff: Backoff) -> Result<Self, Error> {
+        // TLS, if any, is handled by the connection itself
+        let url = format!("ws://{}{}", peer.authority(), path);
+        let socket = connect(&peer, &url).await?;
+        let (commands_tx, commands_rx) = mpsc::unbounded();
+
+        let driver = Driver {
+            peer,
+            url,
+            backoff,
+            commands: commands_rx,
+            subscriptions: HashMap::new(),
+            pending: BTreeMap::new(),
+        };
+        tokio::spawn(driver.run(socket));
+
+        Ok(Self {
+            commands: commands_tx,
+        })
+    }
+
+    /// Subscribe to the events matching the given query.
+    ///
+    /// Only one subscription per query can be active at a time.
+    pub async fn subscribe(&self, query: impl Into<Query>) -> Result<Subscription, Error> {
+        let query = query.into();
+        let (events_tx, events_rx) = mpsc::unbounded();
+
+        self.send(|result| Command::Subscribe {
+            query: query.to_string(),
+            events: events_tx,
+            result,
+        })
+        .await?;
+
+        Ok(Subscription {
+            query,
+            events: events_rx,
+            _client: self.commands.clone(),
+        })
+    }
+
+    /// Unsubscribe from the events matching the given query, ending the stream
+    /// of its subscription
+    pub async fn unsubscribe(&self, query: &Query) -> Result<(), Error> {
+        self.send(|result| Command::Unsubscribe {
+            query: query.to_string(),
+            result,
+        })
+        .await
+    }
+
+    /// Unsubscribe from all events, ending the streams of all subscriptions
+    pub async fn unsubscribe_all(&self) -> Result<(), Error> {
+        self.send(|result| Command::UnsubscribeAll { result }).await
+    }
+
+    async fn send<F>(&self, command: F) -> Result<(), Error>
+    where
+        F: FnOnce(oneshot::Sender<Result<(), Error>>) -> Command,
+    {
+        let stopped = || Error::websocket_error("subscription client stopped");
+        let (result_tx, result_rx) = oneshot::channel();
+
+        self.commands
+            .unbounded_send(command(result_tx))
+            .map_err(|_| stopped())?;
+
+        result_rx.await.map_err(|_| stopped())?
+    }
+}
+
+/// Stream of the events matching the query of a subscription.
+///
+/// Yields an error whenever events may have been lost, eg. when the connection
+/// to the node dropped. Ends once unsubscribed; dropping it unsubscribes upon
+/// the next matching event.
+pub struct Subscription {
+    query: Query,
+    events: mpsc::UnboundedReceiver<Result<ResultEvent, Error>>,
+
+    /// Keeps the connection alive for as long as the subscription
+    _client: mpsc::UnboundedSender<Command>,
+}
+
+impl Subscription {
+    /// Get the query of this subscription
+    pub fn query(&self) -> &Query {
+        &self.query
+    }
+}
+
+impl Stream for Subscription {
+    type Item = Result<ResultEvent, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.events).poll_next(cx)
+    }
+}
+
+/// Commands sent by the client to the task driving the connection
+enum Command {
+    Subscribe {
+        query: String,
+        events: mpsc::UnboundedSender<Result<ResultEvent, Error>>,
+        result: oneshot::Sender<Result<(), Error>>,
+    },
+    Unsubscribe {
+        query: String,
+        result: oneshot::Sender<Result<(), Error>>,
+    },
+    UnsubscribeAll {
+        result: oneshot::Sender<Result<(), Error>>,
+    },
+}
+
+/// Active subscription, by query
+struct ActiveSubscription {
+    /// ID of the `subscribe` request on the current connection, if sent
+    id: Option<Id>,
+
+    /// Sender of the events of the subscription
+    events: mpsc::UnboundedSender<Result<ResultEvent, Error>>,
+
+    /// Sender of the result of the subscription, until confirmed
+    result: Option<oneshot::Sender<Result<(), Error>>>,
+}
+
+/// Request awaiting a response, by ID
+enum PendingRequest {
+    Subscribe(String),
+    Unsubscribe(Option<oneshot::Sender<Result<(), Error>>>),
+}
+
+enum Input {
+    Command(Option<Command>),
+    Message(Option<Result<Message, async_tungstenite::tungstenite::Error>>),
+}
+
+/// Open a websocket connection to the given server, at the given URL
+async fn connect(peer: &Peer, url: &str) -> Result<WebSocket, Error> {
+    let stream = peer
+        .connect()
+        .await
+        .map_err(|e| Error::websocket_error(e.to_string()))?;
+    let (socket, _) = client_async(url, stream).await?;
+    Ok(socket)
+}
+
+/// Task driving the connection, reconnecting when it drops
+struct Driver {
+    peer: Peer,
+    url: String,
+    backoff: Backoff,
+    commands: mpsc::UnboundedReceiver<Command>,
+    subscriptions: HashMap<String, ActiveSubscription>,
+    pending: BTreeMap<Id, PendingRequest>,
+}
+
+impl Driver {
+    async fn run(mut self, mut socket: WebSocket) {
+        loop {
+            match self.run_connection(&mut socket).await {
+                Ok(()) => return,
+                Err(error) => self.connection_lost(error),
+            }
+
+            socket = match self.reconnect().await {
+                Some(socket) => socket,
+                None => return,
+            };
+        }
+    }
+
+    /// Drive the connection until it drops, or until the client and all its
+    /// subscriptions have been dropped
+    async fn run_connection(&mut self, socket: &mut WebSocket) -> Result<(), Error> {
+        let queries = self.subscriptions.keys().cloned().collect::<Vec<_>>();
+
+        for query in queries {
+            self.send_subscribe(socket, query).await?;
+        }
+
+        loop {
+            let input = tokio::select! {
+                command = self.commands.next() => Input::Command(command),
+                message = socket.next() => Input::Message(message),
+            };
+
+            match input {
+                Input::Command(Some(command)) => {
+                    self.handle_command(command, Some(&mut *socket)).await?
+                }
+                Input::Command(None) => {
+                    let _ = socket.close(None).await;
+                    return Ok(());
+                }
+                Input::Message(Some(message)) => self.handle_message(message?, socket).await?,
+                Input::Message(None) => return Err(Error::websocket_error("web socket closed")),
+            }
+        }
+    }
+
+    /// Reconnect with exponential backoff, handling commands in the meantime.
+    ///
+    /// Returns `None` if the client and all its subscriptions have been dropped.
+    async fn reconnect(&mut self) -> Option<WebSocket> {
+        let mut delay = self.backoff.initial;
+
+        loop {
+            let command = tokio::select! {
+                command = self.commands.next() => Some(command),
+                _ = tokio::time::delay_for(delay) => None,
+            };
+
+            match command {
+                Some(Some(command)) => {
+                    let _ = self.handle_command(command, None).await;
+                    continue;
+                }
+                Some(None) => return None,
+                None => (),
+            }
+
+            match connect(&self.peer, &self.url).await {
+                Ok(socket) => return Some(socket),
+                Err(_) => delay = cmp::min(delay * 2, self.backoff.max),
+            }
+        }
+    }
+
+    /// Forget the requests sent on the lost connection, and notify the
+    /// subscriptions that they may miss events
+    fn connection_lost(&mut self, error: Error) {
+        for (_, pending) in mem::take(&mut self.pending) {
+            // The subscription won't be renewed on the next connection
+            if let PendingRequest::Unsubscribe(Some(result)) = pending {
+                let _ = result.send(Ok(()));
+            }
+        }
+
+        for subscription in self.subscriptions.values_mut() {
+            subscription.id = None;
+
+            if subscription.result.is_none() {
+                let _ = subscription.events.unbounded_send(Err(error.clone()));
+            }
+        }
+    }
+
+    /// Handle a command from the client, while connected or not
+    async fn handle_command(
+        &mut self,
+        command: Command,
+        socket: Option<&mut WebSocket>,
+    ) -> Result<(), Error> {
+        match command {
+            Command::Subscribe {
+                query,
+                events,
+                result,
+            } => {
+                if self.subscriptions.contains_key(&query) {
+                    let _ = result.send(Err(Error::invalid_params(&format!(
+                        "already subscribed to {}",
+                        query
+                    ))));
+                    return Ok(());
+                }
+
+                self.subscriptions.insert(
+                    query.clone(),
+                    ActiveSubscription {
+                        id: None,
+                        events,
+                        result: Some(result),
+                    },
+                );
+
+                // Otherwise subscribed to upon reconnection
+                if let Some(socket) = socket {
+                    self.send_subscribe(socket, query).await?;
+                }
+            }
+            Command::Unsubscribe { query, result } => {
+                if self.subscriptions.remove(&query).is_none() {
+                    let _ = result.send(Err(Error::invalid_params(&format!(
+                        "not subscribed to {}",
+                        query
+                    ))));
+                    return Ok(());
+                }
+
+                match socket {
+                    Some(socket) => {
+                        let request = unsubscribe::Request::new(query);
+                        let pending = PendingRequest::Unsubscribe(Some(result));
+                        self.send_request(socket, request, pending).await?;
+                    }
+                    None => {
+                        let _ = result.send(Ok(()));
+                    }
+                }
+            }
+            Command::UnsubscribeAll { result } => {
+                self.subscriptions.clear();
+
+                match socket {
+                    Some(socket) => {
+                        let request = unsubscribe_all::Request;
+                        let pending = PendingRequest::Unsubscribe(Some(result));
+                        self.send_request(socket, request, pending).await?;
+                    }
+                    None => {
+                        let _ = result.send(Ok(()));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handle a message from the node: either the response to a request, or
+    /// an event
+    async fn handle_message(
+        &mut self,
+        message: Message,
+        socket: &mut WebSocket,
+    ) -> Result<(), Error> {
+        let text = match message {
+            Message::Text(text) => text,
+            _ => return Ok(()),
+        };
+
+        // Ignore anything which isn't a JSONRPC response
+        let wrapper = match serde_json::from_str::<response::Wrapper<serde_json::Value>>(&text) {
+            Ok(wrapper) => wrapper,
+            Err(_) => return Ok(()),
+        };
+
+        let id = wrapper.id().clone();
+        let result = wrapper.into_result();
+
+        if let Some(pending) = self.pending.remove(&id) {
+            self.handle_response(&id, pending, result.map(|_| ()));
+            return Ok(());
+        }
+
+        let event = result.and_then(|value| {
+            serde_json::from_value::<ResultEvent>(value).map_err(Error::parse_error)
+        });
+
+        let query = match self.route(&id, event.as_ref().ok()) {
+            Some(query) => query,
+            None => return Ok(()),
+        };
+
+        let delivered = self.subscriptions[&query]
+            .events
+            .unbounded_send(event)
+            .is_ok();
+
+        // The subscription was dropped: unsubscribe from its query
+        if !delivered {
+            self.subscriptions.remove(&query);
+            let request = unsubscribe::Request::new(query);
+            self.send_request(socket, request, PendingRequest::Unsubscribe(None))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    fn handle_response(&mut self, id: &Id, pending: PendingRequest, result: Result<(), Error>) {
+        match pending {
+            PendingRequest::Subscribe(query) => {
+                let subscription = match self.subscriptions.get_mut(&query) {
+                    // Ignore responses to stale subscriptions
+                    Some(subscription) if subscription.id.as_ref() == Some(id) => subscription,
+                    _ => return,
+                };
+
+                match result {
+                    Ok(()) => {
+                        if let Some(result) = subscription.result.take() {
+                            let _ = result.send(Ok(()));
+                        }
+                    }
+                    Err(error) => {
+                        let subscription = self.subscriptions.remove(&query).unwrap();
+
+                        match subscription.result {
+                            Some(result) => {
+                                let _ = result.send(Err(error));
+                            }
+                            None => {
+                                let _ = subscription.events.unbounded_send(Err(error));
+                            }
+                        }
+                    }
+                }
+            }
+            PendingRequest::Unsubscribe(Some(result_tx)) => {
+                let _ = result_tx.send(result);
+            }
+            PendingRequest::Unsubscribe(None) => (),
+        }
+    }
+
+    /// Find the query of the subscription an event belongs to
+    fn route(&self, id: &Id, event: Option<&ResultEvent>) -> Option<String> {
+        let subscription_id = match id {
+            Id::Str(id) if id.ends_with(EVENT_ID_SUFFIX) => {
+                Id::Str(id[..id.len() - EVENT_ID_SUFFIX.len()].to_owned())
+            }
+            other => other.clone(),
+        };
+
+        self.subscriptions
+            .iter()
+            .find(|(_, subscription)| subscription.id.as_ref() == Some(&subscription_id))
+            .map(|(query, _)| query.clone())
+            .or_else(|| {
+                event
+                    .map(|event| event.query.clone())
+                    .filter(|query| self.subscriptions.contains_key(query))
+            })
+    }
+
+    async fn send_subscribe(&mut self, socket: &mut WebSocket, query: String) -> Result<(), Error> {
+        let request = subscribe::Request::new(query.clone());
+        let pending = PendingRequest::Subscribe(query.clone());
+        let id = self.send_request(socket, request, pending).await?;
+
+        if let Some(subscription) = self.subscriptions.get_mut(&query) {
+            subscription.id = Some(id);
+        }
+
+        Ok(())
+    }
+
+    async fn send_request<R: Request>(
+        &mut self,
+        socket: &mut WebSocket,
+        request: R,
+        pending: PendingRequest,
+    ) -> Result<Id, Error> {
+        let wrapper = request::Wrapper::new(request);
+        let id = wrapper.id().clone();
+        let json = serde_json::to_string_pretty(&wrapper).map_err(Error::parse_error)?;
+
+        self.pending.insert(id.clone(), pending);
+        socket.send(Message::text(json)).await?;
+
+        Ok(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::event_listener::TMEventData;
+    use async_tungstenite::tokio::accept_async;
+    use tokio::net::TcpListener;
+
+    /// Accept a connection, confirm the subscription it sends and publish an
+    /// event for it, before dropping the connection
+    async fn serve_one_event(listener: &mut TcpListener) {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut socket = accept_async(stream).await.unwrap();
+
+        let message = socket.next().await.unwrap().unwrap();
+        let request: request::Wrapper<serde_json::Value> =
+            serde_json::from_str(&message.into_text().unwrap()).unwrap();
+        assert_eq!(request.method(), crate::rpc::Method::Subscribe);
+
+        let id = match request.id() {
+            Id::Str(id) => id.clone(),
+            other => panic!("unexpected request ID: {:?}", other),
+        };
+
+        let response = serde_json::json!({"jsonrpc": "2.0", "id": id, "result": {}});
+        socket
+            .send(Message::text(response.to_string()))
+            .await
+            .unwrap();
+
+        let event = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": format!("{}{}", id, EVENT_ID_SUFFIX),
+            "result": {
+                "query": "tm.event = 'NewBlock'",
+                "data": {
+                    "type": "tendermint/event/NewBlock",
+                    "value": {
+                        "block": null,
+                        "result_begin_block": null,
+                        "result_end_block": null
+                    }
+                },
+                "events": null
+            }
+        });
+        socket.send(Message::text(event.to_string())).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn resubscribes_after_reconnection() {
+        let mut listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = tokio::spawn(async move {
+            serve_one_event(&mut listener).await;
+            serve_one_event(&mut listener).await;
+        });
+
+        let backoff = Backoff {
+            initial: Duration::from_millis(10),
+            max: Duration::from_millis(100),
+        };
+        let client = SubscriptionClient::connect_with_backoff(
+            format!("tcp://127.0.0.1:{}", port).parse().unwrap(),
+            backoff,
+        )
+        .await
+        .unwrap();
+
+        let query = Query::from(crate::rpc::query::EventType::NewBlock);
+        let mut subscription = client.subscribe(query.clone()).await.unwrap();
+        assert_eq!(subscription.query(), &query);
+
+        let event = subscription.next().await.unwrap().unwrap();
+        assert!(matches!(event.data, TMEventData::EventDataNewBlock(_)));
+
+        // The first connection drops
+        assert!(subscription.next().await.unwrap().is_err());
+
+        let event = subscription.next().await.unwrap().unwrap();
+        assert!(matches!(event.data, TMEventData::EventDataNewBlock(_)));
+
+        server.await.unwrap();
+
+        // Subscribing twice to the same query is refused
+        assert!(client.subscribe(query).await.is_err());
+    }
+}
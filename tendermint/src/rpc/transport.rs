@@ -0,0 +1,154 @@
+//! Transports carrying JSONRPC requests to Tendermint nodes
+
+use crate::{
+    net,
+    rpc::{
+        connection::{Connector, Peer, TlsConfig},
+        request,
+        url::Url,
+        Error, Method,
+    },
+};
+use async_trait::async_trait;
+use hyper::header;
+use std::{collections::HashMap, fs, path::PathBuf};
+
+/// Transport of JSONRPC requests, eg. over HTTP
+#[async_trait]
+pub trait Transport: Send + Sync {
+    /// Send a (JSON serialized) request, and get the (JSON serialized) response
+    async fn request(&self, request: String) -> Result<String, Error>;
+}
+
+/// JSONRPC over HTTP, reusing pooled connections to the node
+#[derive(Clone)]
+pub struct HttpTransport {
+    client: hyper::Client<Connector>,
+    uri: String,
+}
+
+impl HttpTransport {
+    /// Send requests to the node at the given TCP or UNIX socket address
+    pub fn new(address: net::Address) -> Self {
+        Self::with_peer(Peer::from_address(address), "/")
+    }
+
+    /// Send requests to the given `http://` or `https://` URL, authenticating
+    /// the server with the given TLS settings over `https://`
+    pub fn from_url(url: &Url, tls: TlsConfig) -> Result<Self, Error> {
+        if url.scheme.is_websocket() {
+            return Err(Error::invalid_params(&format!(
+                "invalid RPC URL (expected http:// or https://): {}",
+                url
+            )));
+        }
+
+        Ok(Self::with_peer(Peer::from_url(url, tls)?, &url.path))
+    }
+
+    fn with_peer(peer: Peer, path: &str) -> Self {
+        // TLS, if any, is handled by the connector
+        let uri = format!("http://{}{}", peer.authority(), path);
+        let client = hyper::Client::builder().build(Connector::new(peer));
+
+        Self { client, uri }
+    }
+}
+
+#[async_trait]
+impl Transport for HttpTransport {
+    async fn request(&self, request: String) -> Result<String, Error> {
+        let mut request = hyper::Request::builder()
+            .method("POST")
+            .uri(&self.uri)
+            .body(hyper::Body::from(request.into_bytes()))?;
+
+        {
+            let headers = request.headers_mut();
+            headers.insert(header::CONTENT_TYPE, "application/json".parse().unwrap());
+            headers.insert(
+                header::USER_AGENT,
+                format!("tendermint.rs/{}", env!("CARGO_PKG_VERSION"))
+                    .parse()
+                    .unwrap(),
+            );
+        }
+
+        let response = self.client.request(request).await?;
+        let response_body = hyper::body::to_bytes(response.into_body()).await?;
+        String::from_utf8(response_body.to_vec()).map_err(Error::parse_error)
+    }
+}
+
+/// Replays recorded responses, eg. the JSON fixtures of tests, without any
+/// network access.
+///
+/// Requests for a given method are answered with the contents of the file
+/// `<method>.json` in the fixtures directory, unless another fixture has been
+/// registered for the method. Batches are answered with the fixtures of each
+/// of their requests, with matching IDs.
+#[derive(Clone, Debug)]
+pub struct FixtureTransport {
+    dir: PathBuf,
+    fixtures: HashMap<Method, String>,
+}
+
+impl FixtureTransport {
+    /// Replay the fixtures of the given directory
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            fixtures: HashMap::new(),
+        }
+    }
+
+    /// Answer requests for `method` with the fixture `<name>.json`
+    pub fn fixture(mut self, method: Method, name: impl Into<String>) -> Self {
+        self.fixtures.insert(method, name.into());
+        self
+    }
+
+    /// Read the fixture answering the given request
+    fn read_fixture(&self, request: &request::Wrapper<serde_json::Value>) -> Result<String, Error> {
+        let method = request.method();
+        let name = self
+            .fixtures
+            .get(&method)
+            .cloned()
+            .unwrap_or_else(|| method.to_string());
+        let path = self.dir.join(name + ".json");
+
+        fs::read_to_string(&path)
+            .map_err(|e| Error::http_error(format!("can't read fixture {}: {}", path.display(), e)))
+    }
+}
+
+#[async_trait]
+impl Transport for FixtureTransport {
+    async fn request(&self, request: String) -> Result<String, Error> {
+        let request: serde_json::Value =
+            serde_json::from_str(&request).map_err(Error::parse_error)?;
+
+        let requests = match request {
+            serde_json::Value::Array(requests) => requests,
+            request => {
+                let request = serde_json::from_value(request).map_err(Error::parse_error)?;
+                return self.read_fixture(&request);
+            }
+        };
+
+        let mut responses = vec![];
+
+        for request in requests {
+            let request: request::Wrapper<serde_json::Value> =
+                serde_json::from_value(request).map_err(Error::parse_error)?;
+
+            let mut response: serde_json::Value =
+                serde_json::from_str(&self.read_fixture(&request)?).map_err(Error::parse_error)?;
+            response["id"] = serde_json::to_value(request.id()).unwrap();
+            responses.push(response);
+        }
+
+        Ok(serde_json::Value::Array(responses).to_string())
+    }
+}
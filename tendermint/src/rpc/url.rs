@@ -0,0 +1,215 @@
+//! URLs of Tendermint RPC servers (`http://`, `https://`, `ws://` or `wss://`)
+
+use crate::error::{Error, Kind};
+use anomaly::{fail, format_err};
+use http::Uri;
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// URL scheme of an RPC server
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Scheme {
+    /// JSONRPC over HTTP
+    Http,
+
+    /// JSONRPC over HTTP over TLS
+    Https,
+
+    /// Websocket
+    WebSocket,
+
+    /// Websocket over TLS
+    SecureWebSocket,
+}
+
+impl Scheme {
+    /// Get the URL prefix of this scheme, without the `://` separator
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Scheme::Http => "http",
+            Scheme::Https => "https",
+            Scheme::WebSocket => "ws",
+            Scheme::SecureWebSocket => "wss",
+        }
+    }
+
+    /// Is the connection secured with TLS?
+    pub fn is_secure(self) -> bool {
+        match self {
+            Scheme::Https | Scheme::SecureWebSocket => true,
+            Scheme::Http | Scheme::WebSocket => false,
+        }
+    }
+
+    /// Is this a websocket scheme?
+    pub fn is_websocket(self) -> bool {
+        match self {
+            Scheme::WebSocket | Scheme::SecureWebSocket => true,
+            Scheme::Http | Scheme::Https => false,
+        }
+    }
+
+    /// Port used when the URL doesn't specify one
+    pub fn default_port(self) -> u16 {
+        if self.is_secure() {
+            443
+        } else {
+            80
+        }
+    }
+}
+
+impl Display for Scheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Scheme {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(match s {
+            "http" => Scheme::Http,
+            "https" => Scheme::Https,
+            "ws" => Scheme::WebSocket,
+            "wss" => Scheme::SecureWebSocket,
+            other => fail!(Kind::Parse, "unsupported URL scheme: {}", other),
+        })
+    }
+}
+
+/// URL of an RPC server, eg. `https://rpc.example.com:443/tendermint`
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Url {
+    /// URL scheme
+    pub scheme: Scheme,
+
+    /// Hostname or IP address
+    pub host: String,
+
+    /// Port
+    pub port: u16,
+
+    /// Path, starting with a `/`
+    pub path: String,
+}
+
+impl Url {
+    /// Authority of the URL, ie. its host and port, with IPv6 addresses
+    /// enclosed in brackets
+    pub fn authority(&self) -> String {
+        authority(&self.host, self.port)
+    }
+}
+
+/// Write the given host and port as the authority of a URL, with IPv6
+/// addresses enclosed in brackets
+pub(crate) fn authority(host: &str, port: u16) -> String {
+    if host.contains(':') {
+        format!("[{}]:{}", host, port)
+    } else {
+        format!("{}:{}", host, port)
+    }
+}
+
+impl Display for Url {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}://{}{}", self.scheme, self.authority(), self.path)
+    }
+}
+
+impl FromStr for Url {
+    type Err = Error;
+
+    fn from_str(url: &str) -> Result<Self, Error> {
+        let uri = url
+            .parse::<Uri>()
+            .map_err(|e| format_err!(Kind::Parse, "invalid URL {}: {}", url, e))?;
+
+        let scheme = uri
+            .scheme_str()
+            .ok_or_else(|| format_err!(Kind::Parse, "missing URL scheme: {}", url))?
+            .parse::<Scheme>()?;
+
+        let authority = uri
+            .authority()
+            .ok_or_else(|| format_err!(Kind::Parse, "missing host in URL: {}", url))?;
+
+        // IPv6 addresses are enclosed in brackets
+        let host = authority.host();
+        let port = match authority.port() {
+            Some(port) => port.as_u16(),
+            None if authority.as_str().ends_with(host) => scheme.default_port(),
+            None => fail!(Kind::Parse, "invalid port in URL: {}", url),
+        };
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+
+        if host.is_empty() {
+            fail!(Kind::Parse, "missing host in URL: {}", url);
+        }
+
+        let path = uri
+            .path_and_query()
+            .map(|path| path.as_str())
+            .filter(|path| !path.is_empty())
+            .unwrap_or("/");
+
+        Ok(Self {
+            scheme,
+            host: host.to_owned(),
+            port,
+            path: path.to_owned(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_urls() {
+        let url = "https://rpc.example.com/tendermint/"
+            .parse::<Url>()
+            .unwrap();
+        assert_eq!(url.scheme, Scheme::Https);
+        assert_eq!(url.host, "rpc.example.com");
+        assert_eq!(url.port, 443);
+        assert_eq!(url.path, "/tendermint/");
+
+        let url = "ws://127.0.0.1:26657".parse::<Url>().unwrap();
+        assert_eq!(url.scheme, Scheme::WebSocket);
+        assert_eq!(url.port, 26657);
+        assert_eq!(url.path, "/");
+        assert_eq!(url.to_string(), "ws://127.0.0.1:26657/");
+
+        let url = "http://[::1]:26657".parse::<Url>().unwrap();
+        assert_eq!(url.host, "::1");
+        assert_eq!(url.port, 26657);
+        assert_eq!(url.to_string(), "http://[::1]:26657/");
+
+        let url = "wss://[2001:db8::1]/websocket".parse::<Url>().unwrap();
+        assert_eq!(url.host, "2001:db8::1");
+        assert_eq!(url.port, 443);
+        assert_eq!(url.path, "/websocket");
+        assert_eq!(url.authority(), "[2001:db8::1]:443");
+    }
+
+    #[test]
+    fn reject_invalid_urls() {
+        for url in &[
+            "127.0.0.1:26657",
+            "tcp://127.0.0.1:26657",
+            "https://",
+            "https://:443",
+            "https://localhost:port",
+            "http://[::1]:port",
+            "http://::1:26657",
+        ] {
+            assert!(url.parse::<Url>().is_err(), "parsed: {}", url);
+        }
+    }
+}
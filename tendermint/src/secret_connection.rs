@@ -0,0 +1,340 @@
+//! `SecretConnection`: Transport layer encryption for Tendermint P2P connections.
+//!
+//! Implements the authenticated encryption scheme of Tendermint v0.33:
+//!
+//! - ephemeral X25519 keys are exchanged and used to compute a shared secret
+//! - send and receive keys are derived from the shared secret with HKDF-SHA256
+//! - both peers sign a challenge extracted from a Merlin transcript of the key
+//!   exchange with their long-term Ed25519 (node) key, and verify each other's
+//!   signature
+//! - all subsequent data is sent in ChaCha20-Poly1305 sealed frames
+
+mod amino_types;
+mod kdf;
+mod nonce;
+
+pub use self::{
+    kdf::Kdf,
+    nonce::{Nonce, SIZE as NONCE_SIZE},
+};
+
+use self::amino_types::{AuthSigMessage, AUTH_SIG_MSG_SIZE};
+use crate::error::{Error, Kind};
+use crate::public_key::PublicKey;
+use chacha20poly1305::{
+    aead::{generic_array::GenericArray, AeadInPlace, NewAead},
+    ChaCha20Poly1305,
+};
+use merlin::Transcript;
+use prost_amino::{encoding::encode_varint, Message};
+use rand_core::OsRng;
+use signatory::{
+    ed25519,
+    signature::{Signature, Signer, Verifier},
+};
+use signatory_dalek::Ed25519Verifier;
+use std::{
+    cmp,
+    convert::TryInto,
+    io::{self, Read, Write},
+};
+use subtle::ConstantTimeEq;
+use x25519_dalek::{EphemeralSecret, PublicKey as EphemeralPublic};
+
+/// Size of the MAC tag
+pub const TAG_SIZE: usize = 16;
+
+/// Maximum size of the data carried by a single frame
+pub const DATA_MAX_SIZE: usize = 1024;
+
+/// Size of the length prefix of the data carried by a frame
+const DATA_LEN_SIZE: usize = 4;
+
+/// 4 + 1024 == 1028 total frame size
+const TOTAL_FRAME_SIZE: usize = DATA_MAX_SIZE + DATA_LEN_SIZE;
+
+/// Size of a sealed frame, ie. a frame along with its MAC tag
+const SEALED_FRAME_SIZE: usize = TOTAL_FRAME_SIZE + TAG_SIZE;
+
+/// Size of an amino-encoded ephemeral public key, including the length prefixes
+const EPH_PUBKEY_MSG_SIZE: usize = 34;
+
+/// Merlin transcript labels, as defined by Tendermint
+const TRANSCRIPT_LABEL: &[u8] = b"TENDERMINT_SECRET_CONNECTION_TRANSCRIPT_HASH";
+const LABEL_EPHEMERAL_LOWER_PUBLIC_KEY: &[u8] = b"EPHEMERAL_LOWER_PUBLIC_KEY";
+const LABEL_EPHEMERAL_UPPER_PUBLIC_KEY: &[u8] = b"EPHEMERAL_UPPER_PUBLIC_KEY";
+const LABEL_DH_SECRET: &[u8] = b"DH_SECRET";
+const LABEL_SECRET_CONNECTION_MAC: &[u8] = b"SECRET_CONNECTION_MAC";
+
+/// Encrypted connection between peers in a Tendermint network
+pub struct SecretConnection<IoHandler: Read + Write> {
+    io_handler: IoHandler,
+    recv_nonce: Nonce,
+    send_nonce: Nonce,
+    recv_cipher: ChaCha20Poly1305,
+    send_cipher: ChaCha20Poly1305,
+    remote_pubkey: ed25519::PublicKey,
+    recv_buffer: Vec<u8>,
+}
+
+impl<IoHandler: Read + Write> SecretConnection<IoHandler> {
+    /// Performs a handshake over the given stream, authenticating with the given
+    /// Ed25519 (node) key, and returns a new authenticated `SecretConnection`.
+    pub fn new(
+        mut io_handler: IoHandler,
+        local_pubkey: &ed25519::PublicKey,
+        local_signer: &dyn Signer<ed25519::Signature>,
+    ) -> Result<Self, Error> {
+        // Generate ephemeral keys for perfect forward secrecy.
+        let local_eph_privkey = EphemeralSecret::new(&mut OsRng);
+        let local_eph_pubkey = EphemeralPublic::from(&local_eph_privkey);
+
+        // Write local ephemeral pubkey and receive one too.
+        // NOTE: every 32-byte string is accepted as a Curve25519 public key
+        // (see DJB's Curve25519 paper: http://cr.yp.to/ecdh/curve25519-20060209.pdf)
+        let remote_eph_pubkey = share_eph_pubkey(&mut io_handler, &local_eph_pubkey)?;
+
+        // Sort by lexical order.
+        let local_eph_pubkey_bytes = *local_eph_pubkey.as_bytes();
+        let (low_eph_pubkey_bytes, high_eph_pubkey_bytes) =
+            sort32(local_eph_pubkey_bytes, *remote_eph_pubkey.as_bytes());
+
+        let mut transcript = Transcript::new(TRANSCRIPT_LABEL);
+        transcript.append_message(LABEL_EPHEMERAL_LOWER_PUBLIC_KEY, &low_eph_pubkey_bytes);
+        transcript.append_message(LABEL_EPHEMERAL_UPPER_PUBLIC_KEY, &high_eph_pubkey_bytes);
+
+        // Check if the local ephemeral public key was the least, lexicographically sorted.
+        let loc_is_least = local_eph_pubkey_bytes == low_eph_pubkey_bytes;
+
+        // Compute common shared secret.
+        let shared_secret = local_eph_privkey.diffie_hellman(&remote_eph_pubkey);
+
+        // Reject all-zero outputs from X25519, ie. remote keys of low order.
+        if bool::from(shared_secret.as_bytes().ct_eq(&[0u8; 32])) {
+            return Err(Kind::Crypto
+                .context("low-order ephemeral public key from remote peer")
+                .into());
+        }
+
+        transcript.append_message(LABEL_DH_SECRET, shared_secret.as_bytes());
+
+        let kdf = Kdf::derive_secrets_and_challenge(shared_secret.as_bytes(), loc_is_least);
+
+        let mut challenge = [0u8; 32];
+        transcript.challenge_bytes(LABEL_SECRET_CONNECTION_MAC, &mut challenge);
+
+        let mut sc = SecretConnection {
+            io_handler,
+            recv_nonce: Nonce::default(),
+            send_nonce: Nonce::default(),
+            recv_cipher: ChaCha20Poly1305::new(GenericArray::from_slice(&kdf.recv_secret)),
+            send_cipher: ChaCha20Poly1305::new(GenericArray::from_slice(&kdf.send_secret)),
+            // Replaced by the authenticated remote public key below
+            remote_pubkey: *local_pubkey,
+            recv_buffer: vec![],
+        };
+
+        // Sign the challenge bytes for authentication.
+        let local_signature = local_signer
+            .try_sign(&challenge)
+            .map_err(|e| Kind::Crypto.context(e))?;
+
+        // Share (in secret) each other's pubkey & challenge signature
+        let auth_sig_msg = share_auth_signature(&mut sc, local_pubkey, &local_signature)?;
+
+        let remote_pubkey = ed25519::PublicKey::from_bytes(&auth_sig_msg.key)
+            .ok_or_else(|| Kind::InvalidKey.context("invalid remote public key"))?;
+        let remote_signature = ed25519::Signature::from_bytes(&auth_sig_msg.sig)
+            .map_err(|e| Kind::SignatureInvalid.context(e))?;
+
+        Ed25519Verifier::from(&remote_pubkey)
+            .verify(&challenge, &remote_signature)
+            .map_err(|e| Kind::SignatureInvalid.context(e))?;
+
+        // We've authorized.
+        sc.remote_pubkey = remote_pubkey;
+
+        Ok(sc)
+    }
+
+    /// Returns the authenticated public key of the remote peer
+    pub fn remote_pubkey(&self) -> PublicKey {
+        PublicKey::from(self.remote_pubkey)
+    }
+
+    /// Encrypt the given chunk of data into a sealed frame
+    fn encrypt(&mut self, chunk: &[u8]) -> Result<[u8; SEALED_FRAME_SIZE], Error> {
+        debug_assert!(chunk.len() <= DATA_MAX_SIZE);
+
+        let mut sealed_frame = [0u8; SEALED_FRAME_SIZE];
+        sealed_frame[..DATA_LEN_SIZE].copy_from_slice(&(chunk.len() as u32).to_le_bytes());
+        sealed_frame[DATA_LEN_SIZE..DATA_LEN_SIZE + chunk.len()].copy_from_slice(chunk);
+
+        let tag = self
+            .send_cipher
+            .encrypt_in_place_detached(
+                GenericArray::from_slice(self.send_nonce.as_bytes()),
+                b"",
+                &mut sealed_frame[..TOTAL_FRAME_SIZE],
+            )
+            .map_err(|_| Kind::Crypto.context("encryption failed"))?;
+
+        sealed_frame[TOTAL_FRAME_SIZE..].copy_from_slice(tag.as_slice());
+        self.send_nonce.increment();
+
+        Ok(sealed_frame)
+    }
+
+    /// Decrypt the given sealed frame, returning the chunk of data it carries
+    fn decrypt(&mut self, sealed_frame: &mut [u8; SEALED_FRAME_SIZE]) -> Result<Vec<u8>, Error> {
+        let (frame, tag) = sealed_frame.split_at_mut(TOTAL_FRAME_SIZE);
+
+        self.recv_cipher
+            .decrypt_in_place_detached(
+                GenericArray::from_slice(self.recv_nonce.as_bytes()),
+                b"",
+                frame,
+                GenericArray::from_slice(tag),
+            )
+            .map_err(|_| Kind::Crypto.context("decryption failed"))?;
+
+        self.recv_nonce.increment();
+
+        let chunk_length = u32::from_le_bytes(frame[..DATA_LEN_SIZE].try_into().unwrap()) as usize;
+
+        if chunk_length > DATA_MAX_SIZE {
+            return Err(Kind::Length
+                .context(format!(
+                    "chunk length {} is greater than maximum data size {}",
+                    chunk_length, DATA_MAX_SIZE
+                ))
+                .into());
+        }
+
+        Ok(frame[DATA_LEN_SIZE..DATA_LEN_SIZE + chunk_length].to_vec())
+    }
+}
+
+impl<IoHandler: Read + Write> Read for SecretConnection<IoHandler> {
+    // CONTRACT: data smaller than DATA_MAX_SIZE is read atomically.
+    fn read(&mut self, data: &mut [u8]) -> io::Result<usize> {
+        if !self.recv_buffer.is_empty() {
+            let n = cmp::min(data.len(), self.recv_buffer.len());
+            data[..n].copy_from_slice(&self.recv_buffer[..n]);
+            self.recv_buffer.drain(..n);
+            return Ok(n);
+        }
+
+        let mut sealed_frame = [0u8; SEALED_FRAME_SIZE];
+
+        // The connection being closed between two frames is a regular end of stream
+        let n = self.io_handler.read(&mut sealed_frame)?;
+        if n == 0 {
+            return Ok(0);
+        }
+
+        self.io_handler.read_exact(&mut sealed_frame[n..])?;
+
+        let chunk = self
+            .decrypt(&mut sealed_frame)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let n = cmp::min(data.len(), chunk.len());
+        data[..n].copy_from_slice(&chunk[..n]);
+        self.recv_buffer.extend_from_slice(&chunk[n..]);
+
+        Ok(n)
+    }
+}
+
+impl<IoHandler: Read + Write> Write for SecretConnection<IoHandler> {
+    // Writes encrypted frames of `TOTAL_FRAME_SIZE + TAG_SIZE`.
+    // CONTRACT: data smaller than DATA_MAX_SIZE is written atomically.
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        for chunk in data.chunks(DATA_MAX_SIZE) {
+            let sealed_frame = self
+                .encrypt(chunk)
+                .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+
+            self.io_handler.write_all(&sealed_frame)?;
+        }
+
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.io_handler.flush()
+    }
+}
+
+/// Send our ephemeral public key and receive the one of the remote peer.
+fn share_eph_pubkey<IoHandler: Read + Write>(
+    io_handler: &mut IoHandler,
+    local_eph_pubkey: &EphemeralPublic,
+) -> Result<EphemeralPublic, Error> {
+    let local_eph_pubkey_bytes = local_eph_pubkey.as_bytes();
+
+    // Note: this is not regular protobuf encoding but raw length prefixed amino encoding;
+    // amino prefixes with the total length, and the raw bytes array's length, too:
+    let mut buf = Vec::with_capacity(EPH_PUBKEY_MSG_SIZE);
+    encode_varint((local_eph_pubkey_bytes.len() + 1) as u64, &mut buf); // 33
+    encode_varint(local_eph_pubkey_bytes.len() as u64, &mut buf); // 32
+    buf.extend_from_slice(local_eph_pubkey_bytes);
+
+    // TODO: on the Go side sending and receiving is done in parallel,
+    // here we send and then receive, which works as the message is small.
+    io_handler
+        .write_all(&buf)
+        .map_err(|e| Kind::Io.context(e))?;
+
+    let mut buf = [0u8; EPH_PUBKEY_MSG_SIZE];
+    io_handler
+        .read_exact(&mut buf)
+        .map_err(|e| Kind::Io.context(e))?;
+
+    if buf[0] != 33 || buf[1] != 32 {
+        return Err(Kind::Protocol
+            .context("malformed ephemeral public key message")
+            .into());
+    }
+
+    // this is the 32 byte ephemeral public key of the remote peer:
+    let mut remote_eph_pubkey = [0u8; 32];
+    remote_eph_pubkey.copy_from_slice(&buf[2..]);
+
+    Ok(EphemeralPublic::from(remote_eph_pubkey))
+}
+
+/// Send our public key and challenge signature over the secret connection,
+/// and receive the ones of the remote peer.
+fn share_auth_signature<IoHandler: Read + Write>(
+    sc: &mut SecretConnection<IoHandler>,
+    pubkey: &ed25519::PublicKey,
+    signature: &ed25519::Signature,
+) -> Result<AuthSigMessage, Error> {
+    let auth_sig_msg = AuthSigMessage {
+        key: pubkey.as_bytes().to_vec(),
+        sig: signature.as_ref().to_vec(),
+    };
+
+    let mut buf = Vec::with_capacity(AUTH_SIG_MSG_SIZE);
+    auth_sig_msg
+        .encode_length_delimited(&mut buf)
+        .map_err(|e| Kind::Protocol.context(e))?;
+
+    sc.write_all(&buf).map_err(|e| Kind::Io.context(e))?;
+
+    let mut buf = [0u8; AUTH_SIG_MSG_SIZE];
+    sc.read_exact(&mut buf).map_err(|e| Kind::Io.context(e))?;
+
+    AuthSigMessage::decode_length_delimited(&buf[..]).map_err(|e| Kind::Protocol.context(e).into())
+}
+
+/// Return the given 32 byte arrays, sorted lexicographically.
+fn sort32(first: [u8; 32], second: [u8; 32]) -> ([u8; 32], [u8; 32]) {
+    if second > first {
+        (first, second)
+    } else {
+        (second, first)
+    }
+}
@@ -0,0 +1,23 @@
+//! Amino messages exchanged during the Secret Connection handshake
+
+use prost_amino_derive::Message;
+
+/// Size of an encoded `AuthSigMessage` carrying an Ed25519 key and signature,
+/// including its length prefix:
+///
+/// 1 (length prefix) + 2 (key field) + 4 (amino prefix) + 1 (key length) + 32 (key)
+/// + 2 (signature field) + 64 (signature)
+pub const AUTH_SIG_MSG_SIZE: usize = 106;
+
+/// Authentication message: the local public key along with the signature
+/// of the handshake challenge.
+#[derive(Clone, PartialEq, Message)]
+pub struct AuthSigMessage {
+    /// Ed25519 public key of the sender
+    #[prost_amino(bytes, tag = "1", amino_name = "tendermint/PubKeyEd25519")]
+    pub key: Vec<u8>,
+
+    /// Signature of the challenge
+    #[prost_amino(bytes, tag = "2")]
+    pub sig: Vec<u8>,
+}
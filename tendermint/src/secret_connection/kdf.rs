@@ -0,0 +1,60 @@
+//! Key derivation for Secret Connection
+
+use hkdf::Hkdf;
+use sha2::Sha256;
+use zeroize::Zeroize;
+
+/// Info string used as HKDF domain separator
+const HKDF_INFO: &[u8] = b"TENDERMINT_SECRET_CONNECTION_KEY_AND_CHALLENGE_GEN";
+
+/// Key Derivation Function for `SecretConnection` (HKDF)
+#[derive(Zeroize)]
+#[zeroize(drop)]
+pub struct Kdf {
+    /// Receiver's secret
+    pub recv_secret: [u8; 32],
+
+    /// Sender's secret
+    pub send_secret: [u8; 32],
+
+    /// Challenge to be signed by peer
+    ///
+    /// Only used by handshakes of Tendermint versions prior to v0.33,
+    /// which sign this challenge instead of the Merlin transcript one.
+    pub challenge: [u8; 32],
+}
+
+impl Kdf {
+    /// Returns recv secret, send secret, challenge as 32 byte arrays.
+    ///
+    /// The party whose ephemeral public key is the lowest (`loc_is_lo`)
+    /// receives with the first 32 bytes of derived key material.
+    pub fn derive_secrets_and_challenge(shared_secret: &[u8; 32], loc_is_lo: bool) -> Self {
+        let mut key_material = [0u8; 96];
+
+        Hkdf::<Sha256>::new(None, shared_secret)
+            .expand(HKDF_INFO, &mut key_material)
+            .unwrap();
+
+        let mut recv_secret = [0u8; 32];
+        let mut send_secret = [0u8; 32];
+        let mut challenge = [0u8; 32];
+
+        if loc_is_lo {
+            recv_secret.copy_from_slice(&key_material[0..32]);
+            send_secret.copy_from_slice(&key_material[32..64]);
+        } else {
+            send_secret.copy_from_slice(&key_material[0..32]);
+            recv_secret.copy_from_slice(&key_material[32..64]);
+        }
+
+        challenge.copy_from_slice(&key_material[64..96]);
+        key_material.zeroize();
+
+        Kdf {
+            recv_secret,
+            send_secret,
+            challenge,
+        }
+    }
+}
@@ -0,0 +1,54 @@
+//! Secret Connection nonces
+
+use std::convert::TryInto;
+
+/// Size of a ChaCha20 (IETF) nonce
+pub const SIZE: usize = 12;
+
+/// SecretConnection nonces (i.e. ChaCha20 nonces)
+///
+/// The first 4 bytes are always zero, the last 8 bytes hold a little-endian
+/// counter which is incremented after every sealed or opened frame.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Nonce([u8; SIZE]);
+
+impl Nonce {
+    /// Increment the nonce's counter by 1
+    ///
+    /// # Panics
+    ///
+    /// Panics if the counter overflows, as a nonce must never be reused.
+    pub fn increment(&mut self) {
+        let counter = u64::from_le_bytes(self.0[4..].try_into().unwrap());
+        let counter = counter.checked_add(1).expect("nonce overflow");
+        self.0[4..].copy_from_slice(&counter.to_le_bytes());
+    }
+
+    /// Borrow the nonce as bytes
+    pub fn as_bytes(&self) -> &[u8; SIZE] {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn increment() {
+        let mut nonce = Nonce::default();
+        nonce.increment();
+        assert_eq!(nonce.as_bytes(), &[0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0]);
+
+        let mut nonce = Nonce([0, 0, 0, 0, 255, 255, 0, 0, 0, 0, 0, 0]);
+        nonce.increment();
+        assert_eq!(nonce.as_bytes(), &[0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "nonce overflow")]
+    fn overflow() {
+        let mut nonce = Nonce([0, 0, 0, 0, 255, 255, 255, 255, 255, 255, 255, 255]);
+        nonce.increment();
+    }
+}
@@ -32,6 +32,7 @@
 //! Vec<u8>              <-> HexString:            #[serde(with="serializers::bytes::hexstring")]
 //! Vec<u8>              <-> Base64String:         #[serde(with="serializers::bytes::base64string")]
 //! Vec<u8>              <-> String:               #[serde(with="serializers::bytes::string")]
+//! Vec<Vec<u8>>         <-> [Base64String]:       #[serde(with="serializers::bytes::vec_base64string")]
 //!
 //! Notes:
 //! * Any type that has the "FromStr" trait can be serialized into a string with
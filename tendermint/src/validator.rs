@@ -3,20 +3,41 @@
 use prost_amino_derive::Message;
 use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
 use signatory::{
+    ecdsa::curve::secp256k1,
     ed25519,
     signature::{Signature, Verifier},
 };
 use signatory_dalek::Ed25519Verifier;
+use signatory_secp256k1::EcdsaVerifier;
 use subtle_encoding::base64;
 
 use crate::amino_types::message::AminoMessage;
-use crate::{account, vote, PublicKey};
+use crate::{
+    account,
+    error::{Error, Kind},
+    vote, PublicKey,
+};
+use anomaly::fail;
+use std::collections::BTreeMap;
+
+/// Maximum total voting power of a validator set, so that proposer priorities
+/// can't overflow. Matches `MaxTotalVotingPower` from:
+/// <https://github.com/tendermint/tendermint/blob/v0.33.5/types/validator_set.go>
+pub const MAX_TOTAL_VOTING_POWER: u64 = i64::MAX as u64 / 8;
+
+/// Maximum difference between the proposer priorities of the validators of a
+/// set, as a multiple of its total voting power
+const PRIORITY_WINDOW_SIZE_FACTOR: i64 = 2;
 
 /// Validator set contains a vector of validators
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct Set {
     #[serde(deserialize_with = "parse_vals")]
     validators: Vec<Info>,
+
+    /// Proposer selected by the last increment of the proposer priorities
+    #[serde(skip)]
+    proposer: Option<account::Id>,
 }
 
 impl Set {
@@ -24,7 +45,10 @@ impl Set {
     /// vals is mutable so it can be sorted by address.
     pub fn new(mut vals: Vec<Info>) -> Set {
         vals.sort_by(|v1, v2| v1.address.partial_cmp(&v2.address).unwrap());
-        Set { validators: vals }
+        Set {
+            validators: vals,
+            proposer: None,
+        }
     }
 
     /// Get Info of the underlying validators.
@@ -43,6 +67,262 @@ impl Set {
     }
 }
 
+/// Proposer selection, following Tendermint's weighted round-robin:
+/// <https://github.com/tendermint/tendermint/blob/v0.33.5/types/validator_set.go>
+impl Set {
+    /// Get the validator selected by the last increment of the proposer
+    /// priorities or, if they haven't been incremented, the validator with
+    /// the highest priority, ties being broken in favor of the lowest address.
+    ///
+    /// Validator sets fetched over RPC carry the priorities of their height,
+    /// but not its proposer: the proposer of the next height is the one of
+    /// the set computed with [`next`](#method.next).
+    pub fn proposer(&self) -> Option<Info> {
+        self.proposer
+            .and_then(|address| self.validator(address))
+            .or_else(|| self.proposer_index().map(|index| self.validators[index]))
+    }
+
+    /// Increment the proposer priorities of the validators `times` times,
+    /// selecting a new proposer each time, as done once per height.
+    ///
+    /// Priorities are first rescaled and centered around zero, so that they
+    /// stay within twice the total voting power of one another. Does nothing
+    /// on an empty set.
+    pub fn increment_proposer_priority(&mut self, times: u32) {
+        if self.validators.is_empty() {
+            return;
+        }
+
+        let total_power = self.total_voting_power();
+        self.rescale_priorities(PRIORITY_WINDOW_SIZE_FACTOR * total_power);
+        self.shift_by_avg_proposer_priority();
+
+        for _ in 0..times {
+            for validator in &mut self.validators {
+                validator.set_priority(
+                    validator
+                        .priority()
+                        .saturating_add(validator.power() as i64),
+                );
+            }
+
+            let index = self.proposer_index().unwrap();
+            let proposer = &mut self.validators[index];
+            proposer.set_priority(proposer.priority().saturating_sub(total_power));
+            self.proposer = Some(proposer.address);
+        }
+    }
+
+    /// Apply the validator updates returned by the application at the end
+    /// of a block, as Tendermint's `ValidatorSet.UpdateWithChangeSet` does:
+    ///
+    /// - validators with a voting power of zero are removed
+    /// - updated validators keep their proposer priority
+    /// - new validators start with a priority of about -1.125 times the
+    ///   total voting power, so that unbonding and bonding again doesn't
+    ///   reset a negative priority
+    ///
+    /// The set is left untouched if the updates are invalid, eg. if they
+    /// remove unknown validators or all of them, or contain duplicates.
+    pub fn update(&mut self, updates: &[Update]) -> Result<(), Error> {
+        if updates.is_empty() {
+            return Ok(());
+        }
+
+        let mut changes = BTreeMap::new();
+
+        for update in updates {
+            let address = account::Id::from(update.pub_key);
+
+            if update.power.value() > MAX_TOTAL_VOTING_POWER {
+                fail!(
+                    Kind::OutOfRange,
+                    "voting power of validator {} can't be higher than {}, got {}",
+                    address,
+                    MAX_TOTAL_VOTING_POWER,
+                    update.power.value()
+                );
+            }
+
+            if changes.insert(address, update).is_some() {
+                fail!(
+                    Kind::InvalidBlock,
+                    "duplicate update of validator {}",
+                    address
+                );
+            }
+        }
+
+        let mut removed_power = 0;
+        let mut deltas = vec![];
+
+        for (address, update) in &changes {
+            let current_power = self.validator(*address).map(|validator| validator.power());
+
+            if update.power.is_zero() {
+                match current_power {
+                    Some(power) => removed_power += power as i64,
+                    None => fail!(
+                        Kind::InvalidBlock,
+                        "failed to find validator {} to remove",
+                        address
+                    ),
+                }
+            } else {
+                deltas.push(update.power.value() as i64 - current_power.unwrap_or(0) as i64);
+            }
+        }
+
+        let removals = changes
+            .values()
+            .filter(|update| update.power.is_zero())
+            .count();
+
+        let additions = changes
+            .iter()
+            .filter(|(address, update)| {
+                !update.power.is_zero() && self.validator(**address).is_none()
+            })
+            .count();
+
+        if additions == 0 && removals == self.validators.len() {
+            fail!(
+                Kind::InvalidBlock,
+                "applying the validator updates would result in an empty set"
+            );
+        }
+
+        // Check the total voting power after each update, applying those
+        // decreasing it first
+        deltas.sort_unstable();
+        let mut total_power = self.total_voting_power() - removed_power;
+
+        for delta in deltas {
+            total_power += delta;
+
+            if total_power > MAX_TOTAL_VOTING_POWER as i64 {
+                fail!(
+                    Kind::OutOfRange,
+                    "total voting power can't be higher than {}, got {}",
+                    MAX_TOTAL_VOTING_POWER,
+                    total_power
+                );
+            }
+        }
+
+        // Total voting power after the updates, before the removals
+        let total_power = total_power + removed_power;
+        let new_priority = -(total_power + (total_power >> 3));
+
+        for (address, update) in changes {
+            let index = self
+                .validators
+                .iter()
+                .position(|validator| validator.address == address);
+
+            match (index, update.power.is_zero()) {
+                (Some(index), true) => {
+                    self.validators.remove(index);
+                }
+                (Some(index), false) => {
+                    let validator = &mut self.validators[index];
+                    validator.pub_key = update.pub_key;
+                    validator.voting_power = update.power;
+                }
+                (None, _) => {
+                    let mut validator = Info::new(update.pub_key, update.power);
+                    validator.set_priority(new_priority);
+                    self.validators.push(validator);
+                }
+            }
+        }
+
+        self.validators
+            .sort_by(|v1, v2| v1.address.partial_cmp(&v2.address).unwrap());
+
+        let total_power = self.total_voting_power();
+        self.rescale_priorities(PRIORITY_WINDOW_SIZE_FACTOR * total_power);
+        self.shift_by_avg_proposer_priority();
+
+        Ok(())
+    }
+
+    /// Compute the validator set of the height after the one of this
+    /// validator set, given the validator updates returned by the application
+    /// at the end of the block before it, as Tendermint does once per block.
+    ///
+    /// That is, for the validators of height `H + 1` and the updates of the
+    /// results of height `H`, compute the validators of height `H + 2`, whose
+    /// hash is the `next_validators_hash` of the header of height `H + 1`,
+    /// and whose proposer is the proposer of height `H + 2`.
+    pub fn next(&self, updates: &[Update]) -> Result<Set, Error> {
+        let mut next = self.clone();
+        next.update(updates)?;
+        next.increment_proposer_priority(1);
+        Ok(next)
+    }
+
+    /// Index of the validator with the highest priority, ties being broken
+    /// in favor of the lowest address
+    fn proposer_index(&self) -> Option<usize> {
+        (0..self.validators.len()).max_by(|&i, &j| {
+            let (vi, vj) = (&self.validators[i], &self.validators[j]);
+            vi.priority()
+                .cmp(&vj.priority())
+                .then_with(|| vj.address.cmp(&vi.address))
+        })
+    }
+
+    /// Divide the priorities so that they are at most `diff_max` apart
+    fn rescale_priorities(&mut self, diff_max: i64) {
+        if diff_max <= 0 || self.validators.is_empty() {
+            return;
+        }
+
+        let priorities = self.validators.iter().map(Info::priority);
+        let max = priorities.clone().max().unwrap();
+        let min = priorities.min().unwrap();
+        let diff = max.saturating_sub(min);
+
+        if diff > diff_max {
+            // Rounded up
+            let ratio = (diff - 1) / diff_max + 1;
+
+            for validator in &mut self.validators {
+                validator.set_priority(validator.priority() / ratio);
+            }
+        }
+    }
+
+    /// Center the priorities around zero
+    fn shift_by_avg_proposer_priority(&mut self) {
+        if self.validators.is_empty() {
+            return;
+        }
+
+        let sum: i128 = self
+            .validators
+            .iter()
+            .map(|validator| i128::from(validator.priority()))
+            .sum();
+
+        // Rounded towards negative infinity, as with Go's `big.Int.Div`
+        let avg = sum.div_euclid(self.validators.len() as i128) as i64;
+
+        for validator in &mut self.validators {
+            validator.set_priority(validator.priority().saturating_sub(avg));
+        }
+    }
+
+    fn total_voting_power(&self) -> i64 {
+        self.validators
+            .iter()
+            .map(|validator| validator.power() as i64)
+            .sum()
+    }
+}
+
 // TODO: maybe add a type (with an Option<Vec<Info>> field) instead
 // for light client integration tests only
 fn parse_vals<'de, D>(d: D) -> Result<Vec<Info>, D::Error>
@@ -78,13 +358,18 @@ impl Info {
     /// Verify the given signature against the given sign_bytes using the validators
     /// public key.
     pub fn verify_signature(&self, sign_bytes: &[u8], signature: &[u8]) -> bool {
-        if let Some(pk) = &self.pub_key.ed25519() {
-            let verifier = Ed25519Verifier::from(pk);
-            if let Ok(sig) = ed25519::Signature::from_bytes(signature) {
-                return verifier.verify(sign_bytes, &sig).is_ok();
-            }
+        match &self.pub_key {
+            PublicKey::Ed25519(pk) => match ed25519::Signature::from_bytes(signature) {
+                Ok(sig) => Ed25519Verifier::from(pk).verify(sign_bytes, &sig).is_ok(),
+                Err(_) => false,
+            },
+            // Signatures are 64 byte `r || s` values over the SHA-256 digest of the
+            // sign bytes, and must be in lower-S form, as in Tendermint.
+            PublicKey::Secp256k1(pk) => match secp256k1::FixedSignature::from_bytes(signature) {
+                Ok(sig) => EcdsaVerifier::from(pk).verify(sign_bytes, &sig).is_ok(),
+                Err(_) => false,
+            },
         }
-        false
     }
 }
 
@@ -98,6 +383,15 @@ impl From<PublicKey> for account::Id {
 }
 
 impl Info {
+    /// Proposer priority of the validator, or zero if unknown
+    fn priority(&self) -> i64 {
+        self.proposer_priority.map_or(0, ProposerPriority::value)
+    }
+
+    fn set_priority(&mut self, priority: i64) {
+        self.proposer_priority = Some(ProposerPriority(priority));
+    }
+
     /// Create a new validator.
     pub fn new(pk: PublicKey, vp: vote::Power) -> Info {
         Info {
@@ -113,10 +407,11 @@ impl Info {
 /// It does not include the address, as that is redundant with the pubkey,
 /// nor the proposer priority, as that changes with every block even if the validator set didn't.
 /// It contains only the pubkey and the voting power, and is amino encoded.
-/// TODO: currently only works for Ed25519 pubkeys
 #[derive(Clone, PartialEq, Message)]
 struct InfoHashable {
-    #[prost_amino(bytes, tag = "1", amino_name = "tendermint/PubKeyEd25519")]
+    /// Amino encoding of the pubkey, including the amino prefix of its type
+    /// (`tendermint/PubKeyEd25519` or `tendermint/PubKeySecp256k1`)
+    #[prost_amino(bytes, tag = "1")]
     pub pub_key: Vec<u8>,
     #[prost_amino(uint64, tag = "2")]
     voting_power: u64,
@@ -126,7 +421,7 @@ struct InfoHashable {
 impl From<&Info> for InfoHashable {
     fn from(info: &Info) -> InfoHashable {
         InfoHashable {
-            pub_key: info.pub_key.as_bytes(),
+            pub_key: info.pub_key.to_amino_bytes(),
             voting_power: info.voting_power.value(),
         }
     }
@@ -146,6 +441,11 @@ impl Info {
 pub struct ProposerPriority(i64);
 
 impl ProposerPriority {
+    /// Create a new proposer priority
+    pub fn new(priority: i64) -> ProposerPriority {
+        ProposerPriority(priority)
+    }
+
     /// Get the current voting power
     pub fn value(self) -> i64 {
         self.0
@@ -214,7 +514,7 @@ where
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use subtle_encoding::hex;
 
     use crate::lite::ValidatorSet;
@@ -222,7 +522,7 @@ mod tests {
     use super::*;
 
     // make a validator from a hex ed25519 pubkey and a voting power
-    fn make_validator(pk_string: &str, vp: u64) -> Info {
+    pub(crate) fn make_validator(pk_string: &str, vp: u64) -> Info {
         let pk = PublicKey::from_raw_ed25519(&hex::decode_upper(pk_string).unwrap()).unwrap();
         Info::new(pk, vote::Power::new(vp))
     }
@@ -262,4 +562,184 @@ mod tests {
             148_151_478_422_287_875 + 158_095_448_483_785_107 + 770_561_664_770_006_272
         );
     }
+
+    // make validators with the given voting powers, in increasing order of address
+    fn make_validators(powers: &[u64]) -> Vec<Info> {
+        let mut keys: Vec<PublicKey> = (1..=powers.len())
+            .map(|i| PublicKey::from_raw_ed25519(&[i as u8; 32]).unwrap())
+            .collect();
+        keys.sort_by_key(|&pk| account::Id::from(pk));
+
+        keys.into_iter()
+            .zip(powers)
+            .map(|(pk, &vp)| Info::new(pk, vote::Power::new(vp)))
+            .collect()
+    }
+
+    fn priorities(val_set: &Set) -> Vec<i64> {
+        val_set.validators().iter().map(Info::priority).collect()
+    }
+
+    // select the proposers of the given number of successive heights
+    fn proposers(val_set: &mut Set, heights: usize) -> Vec<account::Id> {
+        (0..heights)
+            .map(|_| {
+                val_set.increment_proposer_priority(1);
+                val_set.proposer().unwrap().address
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_proposer_selection() {
+        let vals = make_validators(&[10, 10, 10]);
+        let mut val_set = Set::new(vals.clone());
+
+        // Ties are broken in favor of the lowest address
+        assert_eq!(val_set.proposer().unwrap(), vals[0]);
+
+        // Validators with the same voting power take turns
+        let addresses: Vec<_> = vals.iter().map(|val| val.address).collect();
+        assert_eq!(proposers(&mut val_set, 3), addresses);
+        assert_eq!(proposers(&mut val_set, 3), addresses);
+
+        // Otherwise validators are selected in proportion to their voting power
+        let vals = make_validators(&[1, 2, 3]);
+        let mut val_set = Set::new(vals.clone());
+        let expected: Vec<_> = [2, 1, 0, 2, 1, 2]
+            .iter()
+            .map(|&i| vals[i].address)
+            .collect();
+
+        assert_eq!(proposers(&mut val_set, 6), expected);
+        assert_eq!(priorities(&val_set), vec![0, 0, 0]);
+        assert_eq!(proposers(&mut val_set, 6), expected);
+
+        // Incrementing several times at once selects the last proposer
+        let mut val_set = Set::new(vals.clone());
+        val_set.increment_proposer_priority(4);
+        assert_eq!(val_set.proposer().unwrap().address, vals[2].address);
+        assert_eq!(priorities(&val_set), vec![-2, 2, 0]);
+    }
+
+    #[test]
+    fn test_priority_rescaling() {
+        let mut vals = make_validators(&[1, 1]);
+        vals[0].proposer_priority = Some(ProposerPriority::new(100));
+        vals[1].proposer_priority = Some(ProposerPriority::new(-100));
+        let mut val_set = Set::new(vals.clone());
+
+        // Priorities are first scaled down to within twice the total voting
+        // power, and centered around zero
+        val_set.increment_proposer_priority(1);
+        assert_eq!(priorities(&val_set), vec![1, -1]);
+        assert_eq!(val_set.proposer().unwrap().address, vals[0].address);
+    }
+
+    #[test]
+    fn test_validator_set_updates() {
+        let vals = make_validators(&[1, 2, 3, 4]);
+        let mut val_set = Set::new(vals[..3].to_vec());
+        val_set.increment_proposer_priority(1);
+        assert_eq!(val_set.proposer().unwrap().address, vals[2].address);
+        assert_eq!(priorities(&val_set), vec![1, 2, -3]);
+
+        // New validators start with a priority of -1.125 times the total
+        // voting power, before centering
+        let add = Update {
+            pub_key: vals[3].pub_key,
+            power: vote::Power::new(4),
+        };
+        let mut added = val_set.clone();
+        added.update(&[add.clone()]).unwrap();
+        assert_eq!(priorities(&added), vec![4, 5, 0, -8]);
+
+        let next = val_set.next(&[add]).unwrap();
+        assert_eq!(next.proposer().unwrap().address, vals[1].address);
+        assert_eq!(priorities(&next), vec![5, -3, 3, -4]);
+        assert_eq!(next.hash(), Set::new(vals.clone()).hash());
+
+        // Validators are updated in place, or removed
+        let updates = [
+            Update {
+                pub_key: vals[0].pub_key,
+                power: vote::Power::new(0),
+            },
+            Update {
+                pub_key: vals[1].pub_key,
+                power: vote::Power::new(5),
+            },
+        ];
+        let next = val_set.next(&updates).unwrap();
+        let expected = vec![Info::new(vals[1].pub_key, vote::Power::new(5)), vals[2]];
+        assert_eq!(next.hash(), Set::new(expected).hash());
+        assert_eq!(next.proposer().unwrap().address, vals[1].address);
+        assert_eq!(priorities(&next), vec![0, 1]);
+
+        // Invalid updates are rejected, leaving the set untouched
+        let remove = |val: &Info| Update {
+            pub_key: val.pub_key,
+            power: vote::Power::new(0),
+        };
+        let invalid_updates = vec![
+            // Unknown validator
+            vec![remove(&vals[3])],
+            // All validators
+            vals[..3].iter().map(remove).collect(),
+            // Duplicate updates
+            vec![remove(&vals[0]), remove(&vals[0])],
+            // Too much voting power
+            vec![Update {
+                pub_key: vals[3].pub_key,
+                power: vote::Power::new(MAX_TOTAL_VOTING_POWER),
+            }],
+        ];
+
+        for updates in invalid_updates {
+            let mut updated = val_set.clone();
+            assert!(updated.update(&updates).is_err());
+            assert_eq!(updated, val_set);
+        }
+    }
+
+    #[test]
+    fn test_secp256k1_hash_bytes() {
+        let pk = PublicKey::from_raw_secp256k1(
+            &hex::decode_upper(
+                "02950E1CDFCB133D6024109FD489F734EEB4502418E538C28481F22BCE276F248C",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let validator = Info::new(pk, vote::Power::new(10));
+
+        // field 1: amino prefix of `tendermint/PubKeySecp256k1` and compressed key,
+        // field 2: voting power
+        let mut expected = vec![0x0a, 0x26, 0xEB, 0x5A, 0xE9, 0x87, 0x21];
+        expected.extend(pk.as_bytes());
+        expected.extend(&[0x10, 0x0a]);
+
+        assert_eq!(validator.hash_bytes(), expected);
+    }
+
+    #[test]
+    fn test_secp256k1_verify_signature() {
+        use signatory::{
+            ecdsa::curve::secp256k1::SecretKey, public_key::PublicKeyed, signature::Signer,
+        };
+        use signatory_secp256k1::EcdsaSigner;
+
+        let signer = EcdsaSigner::from(&SecretKey::from_bytes(&[0x42u8; 32]).unwrap());
+        let validator = Info::new(
+            PublicKey::from(signer.public_key().unwrap()),
+            vote::Power::new(1),
+        );
+
+        let sign_bytes = b"sign bytes";
+        let signature: secp256k1::FixedSignature = signer.sign(sign_bytes);
+
+        assert!(validator.verify_signature(sign_bytes, signature.as_ref()));
+        assert!(!validator.verify_signature(b"other bytes", signature.as_ref()));
+        assert!(!validator.verify_signature(sign_bytes, &[0u8; 63]));
+    }
 }
@@ -9,6 +9,7 @@
 /// cargo test -- --ignored
 /// ```
 mod rpc {
+    use futures::StreamExt;
     use std::cmp::min;
     use tendermint::abci::Code;
     use tendermint::abci::Log;
@@ -148,23 +149,18 @@ mod rpc {
     #[tokio::test]
     #[ignore]
     async fn event_subscription() {
-        let mut client = tendermint::rpc::event_listener::EventListener::connect(
-            "tcp://127.0.0.1:26657".parse().unwrap(),
-        )
-        .await
-        .unwrap();
-        client
+        let client =
+            tendermint::rpc::SubscriptionClient::connect("tcp://127.0.0.1:26657".parse().unwrap())
+                .await
+                .unwrap();
+        let mut subscription = client
             .subscribe(tendermint::rpc::event_listener::EventSubscription::BlockSubscription)
             .await
             .unwrap();
-        // client.subscribe("tm.event='NewBlock'".to_owned()).await.unwrap();
 
-        // Loop here is helpful when debugging parsing of JSON events
-        // loop{
-        let maybe_result_event = client.get_event().await.unwrap();
+        let maybe_result_event = subscription.next().await.unwrap();
         dbg!(&maybe_result_event);
-        // }
-        let result_event = maybe_result_event.expect("unexpected msg read");
+        let result_event = maybe_result_event.expect("subscription error");
         match result_event.data {
             TMEventData::EventDataNewBlock(nb) => {
                 dbg!("got EventDataNewBlock: {:?}", nb);
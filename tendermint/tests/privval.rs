@@ -0,0 +1,282 @@
+//! Tests for the remote signer ("privval") protocol.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process, thread,
+};
+
+use signatory::{
+    ed25519,
+    signature::{self, Signature, Signer, Verifier},
+};
+use signatory_dalek::Ed25519Verifier;
+use tendermint::amino_types::{
+    remote_error::RemoteErrorCode, vote::Vote, BlockId, SignVoteRequest, SignableMsg,
+    SignedMsgType, SignedVoteResponse, TimeMsg,
+};
+use tendermint::config::{NodeKey, PrivValidatorKey, TendermintConfig};
+use tendermint::privval::{Client, LastSignState, Listener, Request, Response, Server};
+use tendermint::{chain, net};
+
+const CHAIN_ID: &str = "test-chain";
+
+fn chain_id() -> chain::Id {
+    CHAIN_ID.parse().unwrap()
+}
+
+/// Key authenticating the TCP connections between client and server
+fn identity_key() -> ed25519::Seed {
+    NodeKey::load_json_file(&"./tests/support/config/node_key.json")
+        .unwrap()
+        .priv_key
+        .ed25519_keypair()
+        .unwrap()
+        .to_seed()
+}
+
+fn validator_key() -> PrivValidatorKey {
+    PrivValidatorKey::load_json_file(&"./tests/support/config/priv_validator_key.json").unwrap()
+}
+
+fn validator_public_key() -> ed25519::PublicKey {
+    validator_key().pub_key.ed25519().unwrap()
+}
+
+fn server(state_file: &Path) -> Server {
+    let _ = fs::remove_file(state_file);
+    let last_sign_state = LastSignState::load_json_file(state_file).unwrap();
+
+    let signer = validator_key()
+        .priv_key
+        .ed25519_keypair()
+        .unwrap()
+        .to_signer();
+
+    Server::new(chain_id(), validator_public_key(), signer, last_sign_state)
+}
+
+fn temp_file(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("tendermint-privval-{}-{}", process::id(), name))
+}
+
+fn vote_request(height: i64) -> SignVoteRequest {
+    SignVoteRequest {
+        vote: Some(Vote {
+            vote_type: SignedMsgType::PreVote.to_u32(),
+            height,
+            round: 0,
+            validator_address: vec![0xa3; 20],
+            ..Vote::default()
+        }),
+    }
+}
+
+/// Drive the server through the given client until the client disconnects
+fn exercise(mut client: Client) {
+    client.ping().unwrap();
+    assert_eq!(
+        client.public_key().unwrap().ed25519().unwrap(),
+        validator_public_key()
+    );
+
+    // Valid requests are signed with the validator key
+    let request = vote_request(42);
+    let response = client.sign_vote(request.clone()).unwrap();
+    assert!(response.err.is_none());
+
+    let mut sign_bytes = vec![];
+    request.sign_bytes(chain_id(), &mut sign_bytes).unwrap();
+    let signature = ed25519::Signature::from_bytes(&response.vote.unwrap().signature).unwrap();
+    Ed25519Verifier::from(&validator_public_key())
+        .verify(&sign_bytes, &signature)
+        .unwrap();
+
+    // Invalid requests are answered with an error
+    let mut request = vote_request(43);
+    request.vote.as_mut().unwrap().validator_address = vec![];
+    let response = client.sign_vote(request).unwrap();
+    assert!(response.vote.is_none());
+    assert_eq!(
+        response.err.unwrap().code,
+        RemoteErrorCode::RemoteSignerError as i32
+    );
+
+    // Requests regressing from the last signed height are refused
+    let response = client.sign_vote(vote_request(41)).unwrap();
+    assert!(response.vote.is_none());
+    assert_eq!(
+        response.err.unwrap().code,
+        RemoteErrorCode::DoubleSignError as i32
+    );
+}
+
+#[test]
+fn tcp_with_secret_connection() {
+    let listener = Listener::bind(&"tcp://127.0.0.1:0".parse().unwrap()).unwrap();
+
+    // The server checks that the client authenticates with the expected peer ID
+    let addr = match listener.local_address().unwrap() {
+        net::Address::Tcp { host, port, .. } => net::Address::Tcp {
+            peer_id: Some(
+                NodeKey::load_json_file(&"./tests/support/config/node_key.json")
+                    .unwrap()
+                    .node_id(),
+            ),
+            host,
+            port,
+        },
+        net::Address::Unix { .. } => unreachable!(),
+    };
+
+    let state_file = temp_file("tcp_state.json");
+    let server = thread::spawn(move || server(&state_file).dial(&addr, &identity_key()));
+
+    exercise(Client::accept(&listener, &identity_key()).unwrap());
+    server.join().unwrap().unwrap();
+}
+
+#[test]
+fn unix_socket_from_config() {
+    let socket_path = temp_file("privval.sock");
+    let _ = fs::remove_file(&socket_path);
+
+    let addr = net::Address::Unix {
+        path: socket_path.clone(),
+    };
+    let listener = Listener::bind(&addr).unwrap();
+
+    let mut config =
+        TendermintConfig::load_toml_file(&PathBuf::from("./tests/support/config/config.toml"))
+            .unwrap();
+    config.priv_validator_laddr = Some(addr);
+
+    let state_file = temp_file("unix_state.json");
+    let server = thread::spawn(move || server(&state_file).dial_config(&config, &identity_key()));
+
+    exercise(Client::accept(&listener, &identity_key()).unwrap());
+    server.join().unwrap().unwrap();
+
+    fs::remove_file(&socket_path).unwrap();
+}
+
+/// Signer whose signing always fails, eg. like an unreachable HSM
+struct FailingSigner;
+
+impl Signer<ed25519::Signature> for FailingSigner {
+    fn try_sign(&self, _msg: &[u8]) -> Result<ed25519::Signature, signature::Error> {
+        Err(signature::Error::new())
+    }
+}
+
+#[test]
+fn signer_error_does_not_update_state() {
+    let state_file = temp_file("signer_error_state.json");
+    let _ = fs::remove_file(&state_file);
+
+    let last_sign_state = LastSignState::load_json_file(&state_file).unwrap();
+    let mut server = Server::new(
+        chain_id(),
+        validator_public_key(),
+        FailingSigner,
+        last_sign_state,
+    );
+
+    let response = sign_vote(&mut server, vote_request(42));
+    assert_eq!(
+        response.err.unwrap().code,
+        RemoteErrorCode::RemoteSignerError as i32
+    );
+
+    // The vote can still be signed once the signer is back
+    let last_sign_state = LastSignState::load_json_file(&state_file).unwrap();
+    assert_eq!(last_sign_state.height().value(), 0);
+
+    fs::remove_file(&state_file).unwrap();
+}
+
+fn sign_vote(server: &mut Server, request: SignVoteRequest) -> SignedVoteResponse {
+    match server.handle(Request::SignVote(request)) {
+        Response::SignedVote(response) => response,
+        other => panic!("unexpected response: {:?}", other),
+    }
+}
+
+#[test]
+fn prevote_then_nil_precommit() {
+    let state_file = temp_file("nil_precommit_state.json");
+    let mut server = server(&state_file);
+
+    let mut prevote = vote_request(42);
+    prevote.vote.as_mut().unwrap().block_id = Some(BlockId::new(vec![0xab; 32], None));
+    let response = sign_vote(&mut server, prevote.clone());
+    assert!(response.err.is_none());
+    let signature = response.vote.unwrap().signature;
+
+    // Signing the same prevote again returns the same signature
+    let response = sign_vote(&mut server, prevote.clone());
+    assert_eq!(response.vote.unwrap().signature, signature);
+
+    // Prevoting for another block is double signing
+    let mut other_prevote = prevote;
+    other_prevote.vote.as_mut().unwrap().block_id = Some(BlockId::new(vec![0xcd; 32], None));
+    let response = sign_vote(&mut server, other_prevote);
+    assert_eq!(
+        response.err.unwrap().code,
+        RemoteErrorCode::DoubleSignError as i32
+    );
+
+    // Precommitting nil at the same height and round is fine
+    let mut precommit = vote_request(42);
+    precommit.vote.as_mut().unwrap().vote_type = SignedMsgType::PreCommit.to_u32();
+    let response = sign_vote(&mut server, precommit);
+    assert!(response.err.is_none());
+    assert!(response.vote.is_some());
+
+    let last_sign_state = LastSignState::load_json_file(&state_file).unwrap();
+    assert_eq!(
+        (last_sign_state.height().value(), last_sign_state.step()),
+        (42, 3)
+    );
+
+    fs::remove_file(&state_file).unwrap();
+}
+
+#[test]
+fn precommit_again_after_restart() {
+    let state_file = temp_file("restart_state.json");
+    let mut server = server(&state_file);
+
+    let mut precommit = vote_request(42);
+    let vote = precommit.vote.as_mut().unwrap();
+    vote.vote_type = SignedMsgType::PreCommit.to_u32();
+    vote.timestamp = Some(TimeMsg {
+        seconds: 1_000,
+        nanos: 0,
+    });
+    let signed = sign_vote(&mut server, precommit.clone()).vote.unwrap();
+
+    // After a restart, consensus signs the same precommit with a new timestamp,
+    // which gets the last signature and timestamp back
+    let last_sign_state = LastSignState::load_json_file(&state_file).unwrap();
+    let mut server = Server::new(
+        chain_id(),
+        validator_public_key(),
+        validator_key()
+            .priv_key
+            .ed25519_keypair()
+            .unwrap()
+            .to_signer(),
+        last_sign_state,
+    );
+
+    precommit.vote.as_mut().unwrap().timestamp = Some(TimeMsg {
+        seconds: 2_000,
+        nanos: 0,
+    });
+    let response = sign_vote(&mut server, precommit);
+    assert!(response.err.is_none());
+    assert_eq!(response.vote.unwrap(), signed);
+
+    fs::remove_file(&state_file).unwrap();
+}
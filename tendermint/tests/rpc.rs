@@ -2,9 +2,11 @@
 
 mod endpoints {
     use std::{fs, path::PathBuf};
-    use tendermint::abci::Code;
+    use tendermint::abci::{self, Code};
+    use tendermint::hash::{Algorithm, Hash};
     use tendermint::lite::Header;
     use tendermint::rpc::{self, endpoint, Response};
+    use tendermint::{account, consensus::RoundStep, vote};
 
     const EXAMPLE_APP: &str = "GaiaApp";
     const EXAMPLE_CHAIN: &str = "cosmoshub-2";
@@ -14,6 +16,15 @@ mod endpoints {
             .unwrap()
     }
 
+    /// Serialize a response and parse it back, checking that its JSON
+    /// representation is preserved
+    fn round_trip<R: Response>(response: &R) -> R {
+        let json = serde_json::to_value(response).unwrap();
+        let parsed: R = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), json);
+        parsed
+    }
+
     #[test]
     fn abci_info() {
         let response = endpoint::abci_info::Response::from_string(&read_json_fixture("abci_info"))
@@ -111,6 +122,16 @@ mod endpoints {
                 .unwrap();
         assert_eq!(response.height.value(), 1814);
 
+        // Results of the transactions, as committed to by the next header
+        let results = response.results();
+        let results_hash = Hash::from_hex_upper(
+            Algorithm::Sha256,
+            "4837665DFE640A370E7496C691987562D02462142C5F34F59E185911A12370EA",
+        )
+        .unwrap();
+        assert_eq!(results.hash(), Some(results_hash));
+        results.proof(2).unwrap().validate(&results_hash).unwrap();
+
         let validator_updates = response.validator_updates;
         let deliver_tx = response.txs_results.unwrap();
         let log_json = &deliver_tx[0].log.parse_json().unwrap();
@@ -134,7 +155,25 @@ mod endpoints {
         assert_eq!(response.block_metas.len(), 10);
 
         let block_meta = &response.block_metas[0];
-        assert_eq!(block_meta.header.chain_id.as_str(), EXAMPLE_CHAIN)
+        assert_eq!(block_meta.header.chain_id.as_str(), EXAMPLE_CHAIN);
+
+        // Block 7 has a single transaction, which succeeded without data
+        let header = &response.block_metas[2].header;
+        assert_eq!(header.height.value(), 8);
+
+        let results = abci::AbciResults::new(vec![abci::AbciResult::default()]);
+        results.verify(header).unwrap();
+        results
+            .proof(0)
+            .unwrap()
+            .validate(&header.last_results_hash.unwrap())
+            .unwrap();
+
+        let results = abci::AbciResults::new(vec![abci::AbciResult {
+            code: Code::from(1),
+            data: Default::default(),
+        }]);
+        assert!(results.verify(header).is_err());
     }
 
     #[test]
@@ -206,6 +245,48 @@ mod endpoints {
         );
     }
 
+    #[test]
+    fn broadcast_evidence() {
+        let response = endpoint::broadcast_evidence::Response::from_string(&read_json_fixture(
+            "broadcast_evidence",
+        ))
+        .unwrap();
+
+        assert_eq!(round_trip(&response).hash.len(), 32);
+
+        // Evidence found in blocks can be submitted again
+        let block =
+            endpoint::block::Response::from_string(&read_json_fixture("block_with_evidences"))
+                .unwrap()
+                .block;
+        let evidence = block.evidence.into_vec().remove(0);
+        let request = endpoint::broadcast_evidence::Request::new(evidence);
+
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["evidence"]["type"], "tendermint/DuplicateVoteEvidence");
+        let parsed: endpoint::broadcast_evidence::Request =
+            serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), json);
+    }
+
+    #[test]
+    fn check_tx() {
+        let response =
+            endpoint::check_tx::Response::from_string(&read_json_fixture("check_tx")).unwrap();
+
+        // Empty fields are omitted by the node
+        assert_eq!(response.code, Code::Ok);
+        assert_eq!(response.gas_wanted.value(), 1);
+        assert_eq!(response.gas_used.value(), 0);
+
+        // Data is base64-encoded, as bytes of ABCI responses are
+        assert_eq!(response.data.as_bytes(), b"satoshi");
+
+        let response = round_trip(&response);
+        assert_eq!(response.data.as_bytes(), b"satoshi");
+        assert_eq!(response.events[0].type_str, "app");
+    }
+
     #[test]
     fn commit() {
         let response =
@@ -221,19 +302,131 @@ mod endpoints {
         assert_eq!(header.hash(), block_id.hash);
     }
 
+    #[test]
+    fn consensus_params() {
+        let response = endpoint::consensus_params::Response::from_string(&read_json_fixture(
+            "consensus_params",
+        ))
+        .unwrap();
+
+        let response = round_trip(&response);
+        assert_eq!(response.block_height.value(), 410_744);
+        assert_eq!(response.consensus_params.block.max_bytes, 200_000);
+        assert_eq!(
+            response.consensus_params.evidence.max_age_num_blocks,
+            100_000
+        );
+
+        // Consensus params are only verified against the header at their height
+        let block = endpoint::block::Response::from_string(&read_json_fixture("block"))
+            .unwrap()
+            .block;
+        assert!(response.verify(&block.header).is_err());
+    }
+
+    #[test]
+    fn consensus_state() {
+        let response =
+            endpoint::consensus_state::Response::from_string(&read_json_fixture("consensus_state"))
+                .unwrap();
+
+        let round_state = round_trip(&response).round_state;
+        let hrs = round_state.height_round_step;
+        assert_eq!(hrs.height.value(), 410_745);
+        assert_eq!(hrs.round, 0);
+        assert_eq!(hrs.step, RoundStep::Prevote);
+        assert_eq!(hrs.to_string(), "410745/0/4");
+        assert!(round_state.proposal_block_hash.is_some());
+        assert!(round_state.locked_block_hash.is_none());
+        assert_eq!(round_state.height_vote_set[0].prevotes.len(), 2);
+        assert_eq!(round_state.proposer.index, 0);
+    }
+
+    #[test]
+    fn dump_consensus_state() {
+        let response = endpoint::dump_consensus_state::Response::from_string(&read_json_fixture(
+            "dump_consensus_state",
+        ))
+        .unwrap();
+
+        let response = round_trip(&response);
+        let round_state = response.round_state;
+        assert_eq!(round_state.height.value(), 410_745);
+        assert_eq!(round_state.step, RoundStep::Prevote);
+        assert_eq!(round_state.validators.validators().len(), 2);
+        assert_eq!(round_state.locked_round, -1);
+        assert_eq!(round_state.votes[0].precommits.len(), 2);
+
+        let peer = &response.peers[0].peer_state;
+        assert_eq!(peer.round_state.step, RoundStep::Propose);
+        assert!(peer.round_state.proposal);
+        assert_eq!(peer.stats.votes, 84_210);
+    }
+
     #[test]
     fn genesis() {
         let response =
             endpoint::genesis::Response::from_string(&read_json_fixture("genesis")).unwrap();
 
+        let genesis = response.genesis;
+        genesis.validate().unwrap();
+
         let tendermint::Genesis {
             chain_id,
             consensus_params,
             ..
-        } = response.genesis;
+        } = &genesis;
 
         assert_eq!(chain_id.as_str(), EXAMPLE_CHAIN);
         assert_eq!(consensus_params.block.max_bytes, 200_000);
+
+        // The initial validators hash to the `validators_hash` of the first
+        // header, here of a chain generated by Tendermint's light client tests
+        let first_block: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(
+                "./tests/support/lite/single_step/sequential/commit/more_than_two_third_vals_sign.json",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let first_header = &first_block["initial"]["signed_header"]["header"];
+        assert_eq!(first_header["height"], "1");
+
+        let mut with_validators = genesis.clone();
+        with_validators.validators = serde_json::from_value(
+            first_block["initial"]["next_validator_set"]["validators"].clone(),
+        )
+        .unwrap();
+        with_validators.validate().unwrap();
+        assert_eq!(
+            with_validators.validators_hash(),
+            Hash::from_hex_upper(
+                Algorithm::Sha256,
+                first_header["validators_hash"].as_str().unwrap()
+            )
+            .unwrap()
+        );
+
+        // The hash of the genesis doesn't depend on the formatting of its JSON
+        let reformatted: tendermint::Genesis =
+            serde_json::from_str(&serde_json::to_string_pretty(&genesis).unwrap()).unwrap();
+        assert_eq!(reformatted.hash().unwrap(), genesis.hash().unwrap());
+
+        let mut no_validators = genesis.clone();
+        no_validators.validators.clear();
+        assert!(no_validators.validate().is_err());
+
+        let mut no_power = genesis.clone();
+        no_power.validators[0].voting_power = vote::Power::new(0);
+        assert!(no_power.validate().is_err());
+
+        let mut wrong_address = genesis.clone();
+        wrong_address.validators[0].address = account::Id::new([0u8; 20]);
+        assert!(wrong_address.validate().is_err());
+
+        let mut no_block_size = genesis;
+        no_block_size.consensus_params.block.max_bytes = 0;
+        assert!(no_block_size.validate().is_err());
     }
 
     #[test]
@@ -250,6 +443,19 @@ mod endpoints {
         assert_eq!(response.peers[0].node_info.network.as_str(), EXAMPLE_CHAIN);
     }
 
+    #[test]
+    fn num_unconfirmed_txs() {
+        let response = endpoint::num_unconfirmed_txs::Response::from_string(&read_json_fixture(
+            "num_unconfirmed_txs",
+        ))
+        .unwrap();
+
+        let response = round_trip(&response);
+        assert_eq!(response.total, 2);
+        assert_eq!(response.total_bytes, 27);
+        assert!(response.txs.is_empty());
+    }
+
     #[test]
     fn status() {
         let response =
@@ -260,6 +466,64 @@ mod endpoints {
         assert_eq!(response.validator_info.voting_power.value(), 0);
     }
 
+    #[test]
+    fn tx() {
+        let response = endpoint::tx::Response::from_string(&read_json_fixture("tx")).unwrap();
+
+        assert_eq!(
+            &response.hash.to_string(),
+            "57D835FBBA0DBF922D8A2EDA56922C9B24E7760927F245A7684A736C4769DB8A"
+        );
+        assert_eq!(response.height.value(), 1207);
+        assert_eq!(response.index, 0);
+        assert_eq!(response.tx.as_bytes(), b"name=satoshi");
+        assert_eq!(response.tx_result.code, Code::Ok);
+        assert_eq!(response.tx_result.events[0].type_str, "app");
+
+        let proof = response.proof.unwrap();
+        assert_eq!(proof.data, response.tx);
+        assert_eq!(proof.proof.total, 2);
+
+        let data_hash = Hash::from_hex_upper(
+            Algorithm::Sha256,
+            "17476C73E7F68228430CA0F1D1F4982B1A78EE9F270DB3648EC49FC1B292667B",
+        )
+        .unwrap();
+        proof.validate(&data_hash).unwrap();
+
+        let other_hash = Hash::from_hex_upper(
+            Algorithm::Sha256,
+            "957462C087E99FE9431839A3FFAD77F0253B27D3FE7EFE012DF5788BA0C211E9",
+        )
+        .unwrap();
+        assert!(proof.validate(&other_hash).is_err());
+    }
+
+    #[test]
+    fn tx_search() {
+        let response =
+            endpoint::tx_search::Response::from_string(&read_json_fixture("tx_search")).unwrap();
+
+        assert_eq!(response.total_count, 2);
+        assert_eq!(response.txs.len(), 2);
+        assert_eq!(response.txs[1].index, 1);
+        assert_eq!(response.txs[1].tx.as_bytes(), b"name=hal");
+        assert!(response.txs[1].proof.is_none());
+    }
+
+    #[test]
+    fn unconfirmed_txs() {
+        let response =
+            endpoint::unconfirmed_txs::Response::from_string(&read_json_fixture("unconfirmed_txs"))
+                .unwrap();
+
+        let response = round_trip(&response);
+        assert_eq!(response.n_txs, 2);
+        assert_eq!(response.total, 2);
+        assert_eq!(response.txs[0].as_bytes(), b"name=satoshi");
+        assert_eq!(response.txs[1].as_bytes(), b"name=hal&age=40");
+    }
+
     #[test]
     fn validators() {
         let response =
@@ -287,3 +551,144 @@ mod endpoints {
         }
     }
 }
+
+mod events {
+    use std::{fs, path::PathBuf};
+    use tendermint::consensus::RoundStep;
+    use tendermint::rpc::{
+        event_listener::{ResultEvent, TMEventData},
+        query::EventType,
+        Response,
+    };
+
+    fn read_event_fixture(name: &str) -> ResultEvent {
+        let json = fs::read_to_string(
+            PathBuf::from("./tests/support/rpc/").join(format!("event_{}.json", name)),
+        )
+        .unwrap();
+
+        ResultEvent::from_string(&json).unwrap()
+    }
+
+    #[test]
+    fn new_block_header() {
+        let event = read_event_fixture("new_block_header");
+        assert_eq!(event.event_type(), Some(EventType::NewBlockHeader));
+
+        match event.data {
+            TMEventData::EventDataNewBlockHeader(data) => {
+                assert_eq!(data.header.height.value(), 10);
+
+                let updates = data.result_end_block.unwrap().validator_updates.unwrap();
+                assert_eq!(updates.len(), 1);
+                assert_eq!(updates[0].power.value(), 1_233_243);
+            }
+            other => panic!("unexpected event data: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn vote() {
+        let event = read_event_fixture("vote");
+        assert_eq!(event.event_type(), Some(EventType::Vote));
+
+        match event.data {
+            TMEventData::EventDataVote(data) => {
+                assert!(data.vote.is_precommit());
+                assert_eq!(data.vote.height.value(), 10);
+                assert_eq!(data.vote.validator_index, 0);
+                assert!(data.vote.block_id.is_some());
+            }
+            other => panic!("unexpected event data: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn new_round() {
+        let event = read_event_fixture("new_round");
+        assert_eq!(event.event_type(), Some(EventType::NewRound));
+
+        match event.data {
+            TMEventData::EventDataNewRound(data) => {
+                assert_eq!(data.height.value(), 11);
+                assert_eq!(data.round, 0);
+                assert_eq!(data.step, RoundStep::NewRound);
+                assert_eq!(data.proposer.index, 0);
+            }
+            other => panic!("unexpected event data: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn complete_proposal() {
+        let event = read_event_fixture("complete_proposal");
+        assert_eq!(event.event_type(), Some(EventType::CompleteProposal));
+
+        match event.data {
+            TMEventData::EventDataCompleteProposal(state) => {
+                assert_eq!(state.height.value(), 11);
+                assert_eq!(state.step, RoundStep::Propose);
+                assert_eq!(state.block_id.unwrap().prefix(), "4FFD15F274");
+            }
+            other => panic!("unexpected event data: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn round_states() {
+        for (name, event_type, round, step) in &[
+            (
+                "new_round_step",
+                EventType::NewRoundStep,
+                0,
+                RoundStep::Prevote,
+            ),
+            ("polka", EventType::Polka, 0, RoundStep::Prevote),
+            ("lock", EventType::Lock, 0, RoundStep::Precommit),
+            ("unlock", EventType::Unlock, 1, RoundStep::Precommit),
+            (
+                "timeout_propose",
+                EventType::TimeoutPropose,
+                0,
+                RoundStep::Propose,
+            ),
+            (
+                "timeout_wait",
+                EventType::TimeoutWait,
+                1,
+                RoundStep::PrecommitWait,
+            ),
+        ] {
+            let event = read_event_fixture(name);
+            assert_eq!(event.event_type(), Some(*event_type));
+
+            match event.data {
+                TMEventData::EventDataRoundState(state) => {
+                    assert_eq!(state.height.value(), 11);
+                    assert_eq!(state.round, *round);
+                    assert_eq!(state.step, *step);
+                    assert_eq!(state.block_id, None);
+                }
+                other => panic!("unexpected event data: {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn validator_set_updates() {
+        let event = read_event_fixture("validator_set_updates");
+        assert_eq!(event.event_type(), Some(EventType::ValidatorSetUpdates));
+
+        match event.data {
+            TMEventData::EventDataValidatorSetUpdates(data) => {
+                let powers = data
+                    .validator_updates
+                    .iter()
+                    .map(|validator| validator.power())
+                    .collect::<Vec<_>>();
+                assert_eq!(powers, [6000, 0]);
+            }
+            other => panic!("unexpected event data: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,317 @@
+//! Tendermint RPC client tests, against local stand-in servers
+
+use async_trait::async_trait;
+use hyper::{server::conn::Http, service::service_fn, Body, Response};
+use rustls::{
+    internal::pemfile::{certs, pkcs8_private_keys},
+    NoClientAuth, ServerConfig,
+};
+use std::{
+    convert::Infallible,
+    env, fs,
+    io::BufReader,
+    path::PathBuf,
+    process,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tendermint::{
+    abci::Transaction,
+    net,
+    rpc::{
+        self, endpoint,
+        subscription::Backoff,
+        transport::{FixtureTransport, Transport},
+        Batch, Client, Method, Retry, TlsConfig, Url,
+    },
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::{TcpListener, UnixListener},
+};
+use tokio_rustls::TlsAcceptor;
+
+fn read_fixture(path: &str) -> Vec<u8> {
+    fs::read(PathBuf::from("./tests/support/").join(path)).unwrap()
+}
+
+/// Answer a single HTTP request with the `health` fixture
+async fn serve_health<S>(stream: S)
+where
+    S: AsyncRead + AsyncWrite + Unpin + 'static,
+{
+    let service = service_fn(|_: hyper::Request<Body>| async {
+        Ok::<_, Infallible>(Response::new(Body::from(read_fixture("rpc/health.json"))))
+    });
+
+    Http::new().serve_connection(stream, service).await.unwrap();
+}
+
+fn tls_acceptor() -> TlsAcceptor {
+    let certs = certs(&mut BufReader::new(&read_fixture("tls/server.pem")[..])).unwrap();
+    let mut keys =
+        pkcs8_private_keys(&mut BufReader::new(&read_fixture("tls/server.key")[..])).unwrap();
+
+    let mut config = ServerConfig::new(NoClientAuth::new());
+    config.set_single_cert(certs, keys.remove(0)).unwrap();
+
+    TlsAcceptor::from(Arc::new(config))
+}
+
+#[tokio::test]
+async fn https_with_custom_ca_roots() {
+    let mut listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let stream = tls_acceptor().accept(stream).await.unwrap();
+        serve_health(stream).await;
+    });
+
+    let url = format!("https://localhost:{}/", port)
+        .parse::<Url>()
+        .unwrap();
+    let tls = TlsConfig::from_ca_roots_pem(&read_fixture("tls/ca.pem")).unwrap();
+    let client = Client::from_url(&url, tls).unwrap();
+
+    client.health().await.unwrap();
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn https_with_untrusted_certificate() {
+    let mut listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        assert!(tls_acceptor().accept(stream).await.is_err());
+    });
+
+    let url = format!("https://localhost:{}/", port)
+        .parse::<Url>()
+        .unwrap();
+    let client = Client::from_url(&url, TlsConfig::default()).unwrap();
+
+    assert!(client.health().await.is_err());
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn unix_socket() {
+    let path = env::temp_dir().join(format!("tendermint-rpc-{}.sock", process::id()));
+    let _ = fs::remove_file(&path);
+    let mut listener = UnixListener::bind(&path).unwrap();
+
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        serve_health(stream).await;
+    });
+
+    let client = Client::new(net::Address::Unix { path: path.clone() });
+
+    client.health().await.unwrap();
+    server.await.unwrap();
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn reject_tls_ip_addresses() {
+    for url in &["https://127.0.0.1:26657", "https://[::1]:26657"] {
+        let url = url.parse::<Url>().unwrap();
+        let error = Client::from_url(&url, TlsConfig::default())
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(error.contains("DNS name"), "{}", error);
+    }
+
+    // Plain HTTP servers can be addressed by IP address
+    let url = "http://[::1]:26657".parse::<Url>().unwrap();
+    assert!(Client::from_url(&url, TlsConfig::default()).is_ok());
+}
+
+#[test]
+fn reject_websocket_urls() {
+    let url = "wss://localhost/websocket".parse::<Url>().unwrap();
+    assert!(Client::from_url(&url, TlsConfig::default()).is_err());
+}
+
+#[tokio::test]
+async fn fixture_transport() {
+    let transport = FixtureTransport::new("./tests/support/rpc/")
+        .fixture(Method::Block, "block_with_evidences");
+    let client = Client::with_transport(transport);
+
+    let status = client.status().await.unwrap();
+    assert_eq!(status.node_info.network.as_str(), "cosmoshub-2");
+
+    let block = client.latest_block().await.unwrap().block;
+    assert_eq!(block.evidence.iter().count(), 1);
+
+    let transport =
+        FixtureTransport::new("./tests/support/rpc/").fixture(Method::Genesis, "missing");
+    assert!(Client::with_transport(transport).genesis().await.is_err());
+}
+
+/// Transport failing its first requests, before replaying fixtures
+struct FlakyTransport {
+    failures: u32,
+    attempts: AtomicU32,
+    fixtures: FixtureTransport,
+}
+
+#[async_trait]
+impl Transport for FlakyTransport {
+    async fn request(&self, request: String) -> Result<String, rpc::Error> {
+        if self.attempts.fetch_add(1, Ordering::SeqCst) < self.failures {
+            return Err(rpc::Error::http_error("connection refused"));
+        }
+
+        self.fixtures.request(request).await
+    }
+}
+
+fn flaky_client(failures: u32, fixtures: FixtureTransport) -> Client<FlakyTransport> {
+    Client::with_transport(FlakyTransport {
+        failures,
+        attempts: AtomicU32::new(0),
+        fixtures,
+    })
+    .retry(Retry {
+        max_attempts: 3,
+        backoff: Backoff {
+            initial: Duration::from_millis(1),
+            max: Duration::from_millis(10),
+        },
+    })
+}
+
+#[tokio::test]
+async fn retries() {
+    let fixtures = FixtureTransport::new("./tests/support/rpc/");
+
+    assert!(flaky_client(2, fixtures.clone()).health().await.is_ok());
+    assert!(flaky_client(3, fixtures.clone()).health().await.is_err());
+
+    // Errors returned by the node are not retried
+    let client = flaky_client(0, fixtures.fixture(Method::Health, "error"));
+    assert!(client.health().await.is_err());
+    assert_eq!(client.transport().attempts.load(Ordering::SeqCst), 1);
+
+    // Neither are requests which aren't idempotent, alone or in a batch
+    let client = flaky_client(1, fixtures.clone());
+    let tx = Transaction::new(b"name=satoshi".to_vec());
+    assert!(client.broadcast_tx_sync(tx.clone()).await.is_err());
+    assert_eq!(client.transport().attempts.load(Ordering::SeqCst), 1);
+
+    let client = flaky_client(1, fixtures);
+    let mut batch = Batch::new();
+    batch.add(endpoint::status::Request);
+    batch.add(endpoint::broadcast::tx_async::Request::new(tx));
+    assert!(!batch.is_idempotent());
+    assert!(client.batch(batch).await.is_err());
+    assert_eq!(client.transport().attempts.load(Ordering::SeqCst), 1);
+}
+
+/// Transport never answering
+struct SilentTransport;
+
+#[async_trait]
+impl Transport for SilentTransport {
+    async fn request(&self, _request: String) -> Result<String, rpc::Error> {
+        futures::future::pending().await
+    }
+}
+
+#[tokio::test]
+async fn timeout() {
+    let client = Client::with_transport(SilentTransport).timeout(Duration::from_millis(10));
+    assert!(client.health().await.is_err());
+}
+
+#[tokio::test]
+async fn batch() {
+    let client = Client::with_transport(FixtureTransport::new("./tests/support/rpc/"));
+
+    let mut batch = Batch::new();
+    let commit = batch.add(endpoint::commit::Request::new(10u64.into()));
+    let validators = batch.add(endpoint::validators::Request::new(10u64.into()));
+    let status = batch.add(endpoint::status::Request);
+
+    let responses = client.batch(batch).await.unwrap();
+    assert_eq!(
+        responses
+            .get(&commit)
+            .unwrap()
+            .signed_header
+            .header
+            .height
+            .value(),
+        10
+    );
+    assert_eq!(responses.get(&validators).unwrap().block_height.value(), 42);
+    assert_eq!(
+        responses.get(&status).unwrap().node_info.network.as_str(),
+        "cosmoshub-2"
+    );
+}
+
+/// Transport paginating the validators of the `validators` fixture, with at
+/// most 30 validators per page
+struct PaginatingTransport;
+
+#[async_trait]
+impl Transport for PaginatingTransport {
+    async fn request(&self, request: String) -> Result<String, rpc::Error> {
+        let request: serde_json::Value = serde_json::from_str(&request).unwrap();
+        let page = request["params"]["page"]
+            .as_str()
+            .unwrap()
+            .parse::<usize>()
+            .unwrap();
+        let per_page = request["params"]["per_page"]
+            .as_str()
+            .unwrap()
+            .parse::<usize>()
+            .unwrap()
+            .min(30);
+
+        let mut response: serde_json::Value =
+            serde_json::from_slice(&read_fixture("rpc/validators.json")).unwrap();
+        let validators = response["result"]["validators"].as_array().unwrap().clone();
+        let page = validators
+            .iter()
+            .skip((page - 1) * per_page)
+            .take(per_page)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        response["result"]["count"] = page.len().to_string().into();
+        response["result"]["total"] = validators.len().to_string().into();
+        response["result"]["validators"] = page.into();
+
+        Ok(response.to_string())
+    }
+}
+
+#[tokio::test]
+async fn paginated_validator_set() {
+    let client = Client::with_transport(PaginatingTransport);
+
+    let first_page = client.validators(42u64).await.unwrap();
+    assert_eq!(first_page.count, 30);
+    assert_eq!(first_page.total, 65);
+
+    let validator_set = client.validator_set(42u64).await.unwrap();
+    assert_eq!(validator_set.validators().len(), 65);
+
+    // Nodes which don't paginate validators return all of them at once
+    let client = Client::with_transport(FixtureTransport::new("./tests/support/rpc/"));
+    let validator_set = client.validator_set(42u64).await.unwrap();
+    assert_eq!(validator_set.validators().len(), 65);
+}
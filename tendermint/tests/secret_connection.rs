@@ -0,0 +1,97 @@
+//! Tests for the Secret Connection handshake and framing.
+//!
+//! Test vectors are located in the `tests/support` directory.
+
+use std::fs;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::thread;
+
+use subtle_encoding::hex;
+use tendermint::config::{NodeKey, PrivValidatorKey};
+use tendermint::private_key::PrivateKey;
+use tendermint::secret_connection::{Kdf, SecretConnection};
+use tendermint::PublicKey;
+
+fn decode_secret(hex_secret: &str) -> [u8; 32] {
+    let mut secret = [0u8; 32];
+    secret.copy_from_slice(&hex::decode(hex_secret).unwrap());
+    secret
+}
+
+/// Test vectors generated by Tendermint's `TestDeriveSecretsAndChallenge`
+#[test]
+fn derive_secrets_and_challenge_golden_test_vectors() {
+    let golden =
+        fs::read_to_string("./tests/support/TestDeriveSecretsAndChallenge.golden").unwrap();
+
+    for line in golden.lines() {
+        let params: Vec<&str> = line.split(',').collect();
+        assert_eq!(params.len(), 5, "malformed test vector: {}", line);
+
+        let rand_secret = decode_secret(params[0]);
+        let loc_is_least = params[1].parse::<bool>().unwrap();
+
+        let kdf = Kdf::derive_secrets_and_challenge(&rand_secret, loc_is_least);
+
+        assert_eq!(kdf.recv_secret, decode_secret(params[2]));
+        assert_eq!(kdf.send_secret, decode_secret(params[3]));
+        assert_eq!(kdf.challenge, decode_secret(params[4]));
+    }
+}
+
+fn connect(
+    stream: UnixStream,
+    private_key: PrivateKey,
+) -> thread::JoinHandle<SecretConnection<UnixStream>> {
+    thread::spawn(move || {
+        let keypair = private_key.ed25519_keypair().unwrap();
+        let public_key = keypair.public_key().ed25519().unwrap();
+
+        SecretConnection::new(stream, &public_key, &keypair.to_signer()).unwrap()
+    })
+}
+
+#[test]
+fn handshake_and_exchange_frames() {
+    let node_key = NodeKey::load_json_file(&"./tests/support/config/node_key.json").unwrap();
+    let validator_key =
+        PrivValidatorKey::load_json_file(&"./tests/support/config/priv_validator_key.json")
+            .unwrap();
+
+    let node_public_key = node_key.public_key();
+    let validator_public_key = validator_key.priv_key.public_key();
+
+    let (node_stream, validator_stream) = UnixStream::pair().unwrap();
+    let node = connect(node_stream, node_key.priv_key);
+    let validator = connect(validator_stream, validator_key.priv_key);
+
+    let mut node_conn = node.join().unwrap();
+    let mut validator_conn = validator.join().unwrap();
+
+    // Each peer authenticated the other one's key
+    assert_eq!(node_conn.remote_pubkey(), validator_public_key);
+    assert_eq!(validator_conn.remote_pubkey(), node_public_key);
+    assert!(matches!(node_conn.remote_pubkey(), PublicKey::Ed25519(_)));
+
+    // Messages larger than a frame are split over several frames
+    let message = (0..2500).map(|i| (i % 256) as u8).collect::<Vec<_>>();
+
+    let sent = message.clone();
+    let writer = thread::spawn(move || {
+        node_conn.write_all(&sent).unwrap();
+        node_conn
+    });
+
+    let mut received = vec![0u8; message.len()];
+    validator_conn.read_exact(&mut received).unwrap();
+    assert_eq!(received, message);
+
+    let mut node_conn = writer.join().unwrap();
+
+    // And the other way around
+    validator_conn.write_all(b"pong").unwrap();
+    let mut received = [0u8; 4];
+    node_conn.read_exact(&mut received).unwrap();
+    assert_eq!(&received, b"pong");
+}